@@ -0,0 +1,44 @@
+//! Runtime API definition for the Stp258 currencies module.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+// The `decl_runtime_apis!` expansion trips these lints.
+#![allow(clippy::too_many_arguments, clippy::unnecessary_mut_passed)]
+
+use codec::Codec;
+use serp_traits::LockIdentifier;
+use sp_std::vec::Vec;
+
+sp_api::decl_runtime_apis! {
+	/// Balances of the native and non-native currencies alike, as routed by
+	/// `stp258_currencies::Pallet`.
+	pub trait Stp258CurrenciesApi<AccountId, CurrencyId, Balance> where
+		AccountId: Codec,
+		CurrencyId: Codec,
+		Balance: Codec,
+	{
+		/// The free balance of `who` in `currency_id`.
+		fn free_balance(currency_id: CurrencyId, who: AccountId) -> Balance;
+
+		/// The reserved balance of `who` in `currency_id`.
+		fn reserved_balance(currency_id: CurrencyId, who: AccountId) -> Balance;
+
+		/// The free and reserved balance of `who` in `currency_id`.
+		fn total_balance(currency_id: CurrencyId, who: AccountId) -> Balance;
+
+		/// The free balance of `who` in `currency_id` that no lock, on
+		/// `stp258_currencies` or its backends, keeps from being
+		/// transferred.
+		fn transferable_balance(currency_id: CurrencyId, who: AccountId) -> Balance;
+
+		/// The total issuance of `currency_id`.
+		fn total_issuance(currency_id: CurrencyId) -> Balance;
+
+		/// The locks set through `stp258_currencies` on `currency_id` of
+		/// `who`.
+		fn locks(currency_id: CurrencyId, who: AccountId) -> Vec<(LockIdentifier, Balance)>;
+
+		/// The total balance of `who` in every registered currency it
+		/// holds.
+		fn all_balances(who: AccountId) -> Vec<(CurrencyId, Balance)>;
+	}
+}
@@ -0,0 +1,138 @@
+//! RPC interface for the Stp258 currencies module.
+
+use codec::Codec;
+use jsonrpc_core::{Error as RpcError, ErrorCode, Result};
+use jsonrpc_derive::rpc;
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_runtime::{
+	generic::BlockId,
+	traits::{Block as BlockT, MaybeDisplay},
+};
+use std::{marker::PhantomData, sync::Arc};
+
+pub use stp258_currencies_rpc_runtime_api::Stp258CurrenciesApi as Stp258CurrenciesRuntimeApi;
+
+mod tests;
+
+/// Balances are sent as decimal strings, as they may not fit in a
+/// JavaScript number.
+#[rpc]
+pub trait Stp258CurrenciesApi<BlockHash, AccountId, CurrencyId> {
+	/// The free balance of `who` in `currency_id`.
+	#[rpc(name = "stp258_freeBalance")]
+	fn free_balance(&self, currency_id: CurrencyId, who: AccountId, at: Option<BlockHash>) -> Result<String>;
+
+	/// The total issuance of `currency_id`.
+	#[rpc(name = "stp258_totalIssuance")]
+	fn total_issuance(&self, currency_id: CurrencyId, at: Option<BlockHash>) -> Result<String>;
+
+	/// The free balance of `who` in `currency_id` that no lock keeps from
+	/// being transferred.
+	#[rpc(name = "stp258_transferable")]
+	fn transferable(&self, currency_id: CurrencyId, who: AccountId, at: Option<BlockHash>) -> Result<String>;
+
+	/// The total balance of `who` in every registered currency it holds.
+	#[rpc(name = "stp258_accountBalances")]
+	fn account_balances(&self, who: AccountId, at: Option<BlockHash>) -> Result<Vec<(CurrencyId, String)>>;
+}
+
+/// Error codes returned by the currencies RPC.
+pub enum Error {
+	/// The call to the runtime failed.
+	RuntimeError,
+}
+
+impl From<Error> for i64 {
+	fn from(e: Error) -> i64 {
+		match e {
+			Error::RuntimeError => 1,
+		}
+	}
+}
+
+/// Serves `Stp258CurrenciesApi` from the runtime API of `client`.
+pub struct Stp258Currencies<C, Block, Balance> {
+	client: Arc<C>,
+	_marker: PhantomData<(Block, Balance)>,
+}
+
+impl<C, Block, Balance> Stp258Currencies<C, Block, Balance> {
+	/// Create a new `Stp258Currencies` serving from `client`.
+	pub fn new(client: Arc<C>) -> Self {
+		Self {
+			client,
+			_marker: Default::default(),
+		}
+	}
+}
+
+fn runtime_error(message: &str, e: impl std::fmt::Debug) -> RpcError {
+	RpcError {
+		code: ErrorCode::ServerError(Error::RuntimeError.into()),
+		message: message.into(),
+		data: Some(format!("{:?}", e).into()),
+	}
+}
+
+impl<C, Block, AccountId, CurrencyId, Balance> Stp258CurrenciesApi<<Block as BlockT>::Hash, AccountId, CurrencyId>
+	for Stp258Currencies<C, Block, Balance>
+where
+	Block: BlockT,
+	C: 'static + ProvideRuntimeApi<Block> + HeaderBackend<Block>,
+	C::Api: Stp258CurrenciesRuntimeApi<Block, AccountId, CurrencyId, Balance>,
+	AccountId: Codec,
+	CurrencyId: Codec,
+	Balance: Codec + MaybeDisplay + Send + Sync + 'static,
+{
+	fn free_balance(
+		&self,
+		currency_id: CurrencyId,
+		who: AccountId,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<String> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+		api.free_balance(&at, currency_id, who)
+			.map(|balance| balance.to_string())
+			.map_err(|e| runtime_error("Unable to query free balance.", e))
+	}
+
+	fn total_issuance(&self, currency_id: CurrencyId, at: Option<<Block as BlockT>::Hash>) -> Result<String> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+		api.total_issuance(&at, currency_id)
+			.map(|balance| balance.to_string())
+			.map_err(|e| runtime_error("Unable to query total issuance.", e))
+	}
+
+	fn transferable(
+		&self,
+		currency_id: CurrencyId,
+		who: AccountId,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<String> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+		api.transferable_balance(&at, currency_id, who)
+			.map(|balance| balance.to_string())
+			.map_err(|e| runtime_error("Unable to query transferable balance.", e))
+	}
+
+	fn account_balances(
+		&self,
+		who: AccountId,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<Vec<(CurrencyId, String)>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+		api.all_balances(&at, who)
+			.map(|balances| {
+				balances
+					.into_iter()
+					.map(|(currency_id, balance)| (currency_id, balance.to_string()))
+					.collect()
+			})
+			.map_err(|e| runtime_error("Unable to query account balances.", e))
+	}
+}
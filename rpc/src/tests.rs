@@ -0,0 +1,157 @@
+//! Unit tests for the Stp258 currencies RPC.
+
+#![cfg(test)]
+
+use super::*;
+use jsonrpc_core::IoHandler;
+use sp_api::{ApiRef, ProvideRuntimeApi};
+use sp_blockchain::{BlockStatus, Info};
+use sp_core::H256;
+use sp_runtime::{
+	testing::{Block as RawBlock, ExtrinsicWrapper, Header},
+	traits::NumberFor,
+};
+
+type Block = RawBlock<ExtrinsicWrapper<u64>>;
+type AccountId = u64;
+type CurrencyId = u32;
+type Balance = u128;
+
+const ALICE: AccountId = 1;
+const SETT: CurrencyId = 2;
+const JUSD: CurrencyId = 3;
+
+/// A balance beyond the precision of a JavaScript number.
+const BIG: Balance = 1_000_000_000_000_000_000_001;
+
+/// A client with a fixed balance of `BIG` SETT and 7 JUSD for `ALICE`.
+struct TestClient;
+
+struct RuntimeApi;
+
+fn free_balance_of(currency_id: CurrencyId, who: AccountId) -> Balance {
+	match (currency_id, who) {
+		(SETT, ALICE) => BIG,
+		(JUSD, ALICE) => 7,
+		_ => 0,
+	}
+}
+
+sp_api::mock_impl_runtime_apis! {
+	impl Stp258CurrenciesRuntimeApi<Block, AccountId, CurrencyId, Balance> for RuntimeApi {
+		fn free_balance(currency_id: CurrencyId, who: AccountId) -> Balance {
+			free_balance_of(currency_id, who)
+		}
+
+		fn reserved_balance(_currency_id: CurrencyId, _who: AccountId) -> Balance {
+			0
+		}
+
+		fn total_balance(currency_id: CurrencyId, who: AccountId) -> Balance {
+			free_balance_of(currency_id, who)
+		}
+
+		fn transferable_balance(currency_id: CurrencyId, who: AccountId) -> Balance {
+			free_balance_of(currency_id, who).saturating_sub(5)
+		}
+
+		fn total_issuance(currency_id: CurrencyId) -> Balance {
+			free_balance_of(currency_id, ALICE)
+		}
+
+		fn locks(_currency_id: CurrencyId, _who: AccountId) -> Vec<([u8; 8], Balance)> {
+			vec![]
+		}
+
+		fn all_balances(who: AccountId) -> Vec<(CurrencyId, Balance)> {
+			if who == ALICE {
+				vec![(SETT, BIG), (JUSD, 7)]
+			} else {
+				vec![]
+			}
+		}
+	}
+}
+
+impl ProvideRuntimeApi<Block> for TestClient {
+	type Api = RuntimeApi;
+
+	fn runtime_api<'a>(&'a self) -> ApiRef<'a, Self::Api> {
+		RuntimeApi.into()
+	}
+}
+
+impl HeaderBackend<Block> for TestClient {
+	fn header(&self, _id: BlockId<Block>) -> sp_blockchain::Result<Option<Header>> {
+		Ok(None)
+	}
+
+	fn info(&self) -> Info<Block> {
+		Info {
+			best_hash: H256::zero(),
+			best_number: 0,
+			genesis_hash: H256::zero(),
+			finalized_hash: H256::zero(),
+			finalized_number: 0,
+			number_leaves: 1,
+		}
+	}
+
+	fn status(&self, _id: BlockId<Block>) -> sp_blockchain::Result<BlockStatus> {
+		Ok(BlockStatus::InChain)
+	}
+
+	fn number(&self, _hash: H256) -> sp_blockchain::Result<Option<NumberFor<Block>>> {
+		Ok(Some(0))
+	}
+
+	fn hash(&self, _number: NumberFor<Block>) -> sp_blockchain::Result<Option<H256>> {
+		Ok(Some(H256::zero()))
+	}
+}
+
+fn io() -> IoHandler {
+	let mut io = IoHandler::new();
+	io.extend_with(Stp258CurrenciesApi::to_delegate(Stp258Currencies::<_, Block, Balance>::new(
+		Arc::new(TestClient),
+	)));
+	io
+}
+
+fn call(method: &str, params: &str) -> Option<String> {
+	io().handle_request_sync(&format!(
+		r#"{{"jsonrpc":"2.0","method":"{}","params":{},"id":1}}"#,
+		method, params
+	))
+}
+
+fn result(result: &str) -> Option<String> {
+	Some(format!(r#"{{"jsonrpc":"2.0","result":{},"id":1}}"#, result))
+}
+
+#[test]
+fn balances_are_served_as_strings() {
+	assert_eq!(
+		call("stp258_freeBalance", "[2, 1]"),
+		result(r#""1000000000000000000000001""#)
+	);
+	assert_eq!(call("stp258_freeBalance", "[3, 4]"), result(r#""0""#));
+	assert_eq!(call("stp258_transferable", "[3, 1]"), result(r#""2""#));
+	assert_eq!(
+		call("stp258_totalIssuance", "[2]"),
+		result(r#""1000000000000000000000001""#)
+	);
+	assert_eq!(
+		call("stp258_accountBalances", "[1]"),
+		result(r#"[[2,"1000000000000000000000001"],[3,"7"]]"#)
+	);
+}
+
+#[test]
+fn queries_at_a_given_block_work() {
+	let at = format!("{:?}", H256::zero());
+	assert_eq!(
+		call("stp258_freeBalance", &format!(r#"[3, 1, "{}"]"#, at)),
+		result(r#""7""#)
+	);
+}
@@ -0,0 +1,480 @@
+//! Benchmarks for the Stp258 currencies module.
+
+#![cfg(feature = "runtime-benchmarks")]
+
+use super::*;
+use frame_benchmarking::{account, benchmarks, impl_benchmark_test_suite, whitelisted_caller};
+use frame_support::traits::UnfilteredDispatchable;
+use frame_system::RawOrigin;
+use sp_runtime::traits::{Bounded, One, Saturating};
+use sp_std::vec;
+
+const SEED: u32 = 0;
+
+/// One whole unit of `currency_id`, never less than the smallest balance.
+fn dollar<T: Config>(currency_id: CurrencyIdOf<T>) -> BalanceOf<T> {
+	<Pallet<T> as Stp258Currency<T::AccountId>>::base_unit(currency_id).max(One::one())
+}
+
+fn register<T: Config>(currency_id: CurrencyIdOf<T>) {
+	let _ = Pallet::<T>::do_set_currency_metadata(currency_id, CurrencyMetadata::default());
+}
+
+fn set_roles<T: Config>(currency_id: CurrencyIdOf<T>, who: &T::AccountId) {
+	Roles::<T>::insert(
+		currency_id,
+		CurrencyRoles {
+			issuer: who.clone(),
+			freezer: who.clone(),
+			admin: who.clone(),
+		},
+	);
+}
+
+fn set_balance<T: Config>(currency_id: CurrencyIdOf<T>, who: &T::AccountId, amount: BalanceOf<T>) {
+	let _ = <Pallet<T> as Stp258Currency<T::AccountId>>::deposit(currency_id, who, amount);
+}
+
+benchmarks! {
+	transfer_non_native_currency {
+		let currency_id = T::GetStp258BenchmarkCurrencyId::get();
+		register::<T>(currency_id);
+		let amount = dollar::<T>(currency_id).saturating_mul(1_000u32.into());
+		let from: T::AccountId = whitelisted_caller();
+		set_balance::<T>(currency_id, &from, amount);
+
+		let to: T::AccountId = account("to", 0, SEED);
+		let to_lookup = T::Lookup::unlookup(to.clone());
+	}: transfer(RawOrigin::Signed(from), to_lookup, currency_id, amount)
+	verify {
+		assert_eq!(<Pallet<T> as Stp258Currency<T::AccountId>>::total_balance(currency_id, &to), amount);
+	}
+
+	transfer_native_currency {
+		let currency_id = T::GetStp258NativeId::get();
+		register::<T>(currency_id);
+		let amount = dollar::<T>(currency_id).saturating_mul(1_000u32.into());
+		let from: T::AccountId = whitelisted_caller();
+		set_balance::<T>(currency_id, &from, amount);
+
+		let to: T::AccountId = account("to", 0, SEED);
+		let to_lookup = T::Lookup::unlookup(to.clone());
+	}: _(RawOrigin::Signed(from), to_lookup, amount)
+	verify {
+		assert_eq!(T::Stp258Native::total_balance(&to), amount);
+	}
+
+	update_balance_non_native_currency {
+		let currency_id = T::GetStp258BenchmarkCurrencyId::get();
+		register::<T>(currency_id);
+		let balance = dollar::<T>(currency_id).saturating_mul(2u32.into());
+		let amount = AmountOf::<T>::try_from(balance)
+			.map_err(|_| "balance conversion failed")?;
+		let who: T::AccountId = account("who", 0, SEED);
+		let call = Call::<T>::update_balance(T::Lookup::unlookup(who.clone()), currency_id, amount);
+		let origin = T::UpdateOrigin::successful_origin();
+	}: { call.dispatch_bypass_filter(origin)? }
+	verify {
+		assert_eq!(<Pallet<T> as Stp258Currency<T::AccountId>>::total_balance(currency_id, &who), balance);
+	}
+
+	update_balance_native_currency_creating {
+		let currency_id = T::GetStp258NativeId::get();
+		register::<T>(currency_id);
+		let balance = dollar::<T>(currency_id).saturating_mul(2u32.into());
+		let amount = AmountOf::<T>::try_from(balance)
+			.map_err(|_| "balance conversion failed")?;
+		let who: T::AccountId = account("who", 0, SEED);
+		let call = Call::<T>::update_balance(T::Lookup::unlookup(who.clone()), currency_id, amount);
+		let origin = T::UpdateOrigin::successful_origin();
+	}: { call.dispatch_bypass_filter(origin)? }
+	verify {
+		assert_eq!(T::Stp258Native::total_balance(&who), balance);
+	}
+
+	update_balance_native_currency_killing {
+		let currency_id = T::GetStp258NativeId::get();
+		register::<T>(currency_id);
+		let balance = dollar::<T>(currency_id).saturating_mul(2u32.into());
+		let amount = AmountOf::<T>::try_from(balance)
+			.map_err(|_| "balance conversion failed")?;
+		let who: T::AccountId = account("who", 0, SEED);
+		set_balance::<T>(currency_id, &who, balance);
+		let call = Call::<T>::update_balance(T::Lookup::unlookup(who.clone()), currency_id, -amount);
+		let origin = T::UpdateOrigin::successful_origin();
+	}: { call.dispatch_bypass_filter(origin)? }
+	verify {
+		assert_eq!(T::Stp258Native::free_balance(&who), Zero::zero());
+	}
+
+	transfer_keep_alive {
+		let currency_id = T::GetStp258BenchmarkCurrencyId::get();
+		register::<T>(currency_id);
+		let amount = dollar::<T>(currency_id).saturating_mul(1_000u32.into());
+		let from: T::AccountId = whitelisted_caller();
+		set_balance::<T>(currency_id, &from, amount.saturating_mul(2u32.into()));
+
+		let to: T::AccountId = account("to", 0, SEED);
+		let to_lookup = T::Lookup::unlookup(to.clone());
+	}: _(RawOrigin::Signed(from), to_lookup, currency_id, amount)
+	verify {
+		assert_eq!(<Pallet<T> as Stp258Currency<T::AccountId>>::total_balance(currency_id, &to), amount);
+	}
+
+	transfer_batch {
+		let n in 1 .. T::MaxBatchTransfers::get();
+
+		let currency_id = T::GetStp258BenchmarkCurrencyId::get();
+		register::<T>(currency_id);
+		let amount = dollar::<T>(currency_id).saturating_mul(1_000u32.into());
+		let from: T::AccountId = whitelisted_caller();
+		set_balance::<T>(currency_id, &from, amount.saturating_mul(n.into()));
+
+		let transfers = (0..n)
+			.map(|i| (T::Lookup::unlookup(account("to", i, SEED)), currency_id, amount))
+			.collect::<Vec<_>>();
+	}: _(RawOrigin::Signed(from), transfers)
+	verify {
+		let to: T::AccountId = account("to", n - 1, SEED);
+		assert_eq!(<Pallet<T> as Stp258Currency<T::AccountId>>::total_balance(currency_id, &to), amount);
+	}
+
+	expire_locks {
+		let n in 0 .. T::MaxExpiringLocksPerBlock::get();
+
+		let currency_id = T::GetStp258BenchmarkCurrencyId::get();
+		register::<T>(currency_id);
+		let until: T::BlockNumber = 2u32.into();
+		frame_system::Module::<T>::set_block_number(One::one());
+		for i in 0..n {
+			let who: T::AccountId = account("who", i, SEED);
+			set_balance::<T>(currency_id, &who, dollar::<T>(currency_id));
+			Pallet::<T>::set_lock_until(*b"expiring", currency_id, &who, dollar::<T>(currency_id), until)?;
+		}
+	}: {
+		Pallet::<T>::on_initialize(until);
+	}
+	verify {
+		assert_eq!(Pallet::<T>::expiring_locks(until), vec![]);
+	}
+
+	transfer_all {
+		let currency_id = T::GetStp258BenchmarkCurrencyId::get();
+		register::<T>(currency_id);
+		// a large balance behind a lock this module does not know of makes
+		// `reducible_balance` search the longest
+		let amount = BalanceOf::<T>::max_value() / 2u32.into();
+		let locked = amount / 3u32.into();
+		let from: T::AccountId = whitelisted_caller();
+		set_balance::<T>(currency_id, &from, amount);
+		T::Stp258Currency::set_lock(*b"benchmrk", currency_id, &from, locked)?;
+
+		let to: T::AccountId = account("to", 0, SEED);
+		let to_lookup = T::Lookup::unlookup(to.clone());
+	}: _(RawOrigin::Signed(from), to_lookup, currency_id, false)
+	verify {
+		assert_eq!(
+			<Pallet<T> as Stp258Currency<T::AccountId>>::total_balance(currency_id, &to),
+			amount - locked
+		);
+	}
+
+	vested_transfer {
+		let currency_id = T::GetStp258BenchmarkCurrencyId::get();
+		register::<T>(currency_id);
+		let schedule = VestingSchedule {
+			start: 0u32.into(),
+			period: 2u32.into(),
+			period_count: 3,
+			per_period: dollar::<T>(currency_id),
+		};
+		let from: T::AccountId = whitelisted_caller();
+		set_balance::<T>(currency_id, &from, dollar::<T>(currency_id).saturating_mul(1_000u32.into()));
+
+		let to: T::AccountId = account("to", 0, SEED);
+		let to_lookup = T::Lookup::unlookup(to.clone());
+	}: _(RawOrigin::Signed(from), to_lookup, currency_id, schedule.clone())
+	verify {
+		assert_eq!(
+			<Pallet<T> as Stp258Currency<T::AccountId>>::total_balance(currency_id, &to),
+			schedule.total_amount().unwrap()
+		);
+	}
+
+	claim {
+		let i in 1 .. T::MaxVestingSchedules::get();
+
+		let currency_id = T::GetStp258BenchmarkCurrencyId::get();
+		let schedule = VestingSchedule {
+			start: 0u32.into(),
+			period: 2u32.into(),
+			period_count: 3,
+			per_period: dollar::<T>(currency_id),
+		};
+		let from: T::AccountId = account("from", 0, SEED);
+		set_balance::<T>(currency_id, &from, dollar::<T>(currency_id).saturating_mul(1_000u32.into()));
+
+		let to: T::AccountId = whitelisted_caller();
+		for _ in 0..i {
+			Pallet::<T>::do_vested_transfer(&from, &to, currency_id, schedule.clone())?;
+		}
+		frame_system::Module::<T>::set_block_number(schedule.end().unwrap() + One::one());
+	}: _(RawOrigin::Signed(to.clone()), currency_id)
+	verify {
+		assert_eq!(Pallet::<T>::vesting_schedules(&to, currency_id), vec![]);
+	}
+
+	update_vesting_schedules {
+		let i in 1 .. T::MaxVestingSchedules::get();
+
+		let currency_id = T::GetStp258BenchmarkCurrencyId::get();
+		let schedule = VestingSchedule {
+			start: 0u32.into(),
+			period: 2u32.into(),
+			period_count: 3,
+			per_period: dollar::<T>(currency_id),
+		};
+		let schedules = vec![schedule; i as usize];
+
+		let to: T::AccountId = account("to", 0, SEED);
+		set_balance::<T>(currency_id, &to, dollar::<T>(currency_id).saturating_mul(1_000u32.into()));
+		let to_lookup = T::Lookup::unlookup(to.clone());
+	}: _(RawOrigin::Root, to_lookup, currency_id, schedules)
+	verify {
+		assert_eq!(Pallet::<T>::vesting_schedules(&to, currency_id).len(), i as usize);
+	}
+
+	register_currency {
+		let currency_id = T::GetStp258BenchmarkCurrencyId::get();
+		CurrencyRegistry::<T>::remove(currency_id);
+		let metadata = CurrencyMetadata {
+			name: b"Setter".to_vec(),
+			symbol: b"SETT".to_vec(),
+			decimals: 12,
+			peg: Some(b"USD".to_vec()),
+			..Default::default()
+		};
+		let call = Call::<T>::register_currency(currency_id, metadata.clone());
+		let origin = T::RegistryOrigin::successful_origin();
+	}: { call.dispatch_bypass_filter(origin)? }
+	verify {
+		assert_eq!(Pallet::<T>::currency_metadata(currency_id), Some(metadata));
+	}
+
+	update_currency {
+		let currency_id = T::GetStp258BenchmarkCurrencyId::get();
+		register::<T>(currency_id);
+		let metadata = CurrencyMetadata {
+			status: CurrencyStatus::Deprecated,
+			..Default::default()
+		};
+		let call = Call::<T>::update_currency(currency_id, metadata.clone());
+		let origin = T::RegistryOrigin::successful_origin();
+	}: { call.dispatch_bypass_filter(origin)? }
+	verify {
+		assert_eq!(Pallet::<T>::currency_metadata(currency_id), Some(metadata));
+	}
+
+	pause_currency {
+		let currency_id = T::GetStp258BenchmarkCurrencyId::get();
+		let call = Call::<T>::pause_currency(currency_id);
+		let origin = T::PauseOrigin::successful_origin();
+	}: { call.dispatch_bypass_filter(origin)? }
+	verify {
+		assert!(Pallet::<T>::is_paused(currency_id));
+	}
+
+	unpause_currency {
+		let currency_id = T::GetStp258BenchmarkCurrencyId::get();
+		PausedCurrencies::<T>::insert(currency_id, true);
+		let call = Call::<T>::unpause_currency(currency_id);
+		let origin = T::PauseOrigin::successful_origin();
+	}: { call.dispatch_bypass_filter(origin)? }
+	verify {
+		assert!(!Pallet::<T>::is_paused(currency_id));
+	}
+
+	freeze_account {
+		let currency_id = T::GetStp258BenchmarkCurrencyId::get();
+		let who: T::AccountId = account("who", 0, SEED);
+		let call = Call::<T>::freeze_account(T::Lookup::unlookup(who.clone()), currency_id);
+		let origin = T::FreezeOrigin::successful_origin();
+	}: { call.dispatch_bypass_filter(origin)? }
+	verify {
+		assert!(Pallet::<T>::is_frozen(&who, currency_id));
+	}
+
+	thaw_account {
+		let currency_id = T::GetStp258BenchmarkCurrencyId::get();
+		let who: T::AccountId = account("who", 0, SEED);
+		FrozenAccounts::<T>::insert(&who, currency_id, true);
+		let call = Call::<T>::thaw_account(T::Lookup::unlookup(who.clone()), currency_id);
+		let origin = T::FreezeOrigin::successful_origin();
+	}: { call.dispatch_bypass_filter(origin)? }
+	verify {
+		assert!(!Pallet::<T>::is_frozen(&who, currency_id));
+	}
+
+	set_roles {
+		let currency_id = T::GetStp258BenchmarkCurrencyId::get();
+		register::<T>(currency_id);
+		let issuer: T::AccountId = account("issuer", 0, SEED);
+		let call = Call::<T>::set_roles(
+			currency_id,
+			T::Lookup::unlookup(issuer.clone()),
+			T::Lookup::unlookup(issuer.clone()),
+			T::Lookup::unlookup(issuer.clone()),
+		);
+		let origin = T::RegistryOrigin::successful_origin();
+	}: { call.dispatch_bypass_filter(origin)? }
+	verify {
+		assert_eq!(Pallet::<T>::roles(currency_id).map(|roles| roles.admin), Some(issuer));
+	}
+
+	transfer_roles {
+		let currency_id = T::GetStp258BenchmarkCurrencyId::get();
+		let admin: T::AccountId = whitelisted_caller();
+		set_roles::<T>(currency_id, &admin);
+		let issuer: T::AccountId = account("issuer", 0, SEED);
+		let issuer_lookup = T::Lookup::unlookup(issuer.clone());
+	}: _(RawOrigin::Signed(admin), currency_id, issuer_lookup.clone(), issuer_lookup.clone(), issuer_lookup)
+	verify {
+		assert_eq!(Pallet::<T>::roles(currency_id).map(|roles| roles.admin), Some(issuer));
+	}
+
+	mint {
+		let currency_id = T::GetStp258BenchmarkCurrencyId::get();
+		register::<T>(currency_id);
+		let issuer: T::AccountId = whitelisted_caller();
+		set_roles::<T>(currency_id, &issuer);
+		let amount = dollar::<T>(currency_id).saturating_mul(1_000u32.into());
+		let to: T::AccountId = account("to", 0, SEED);
+		let to_lookup = T::Lookup::unlookup(to.clone());
+	}: _(RawOrigin::Signed(issuer), currency_id, to_lookup, amount)
+	verify {
+		assert_eq!(<Pallet<T> as Stp258Currency<T::AccountId>>::total_balance(currency_id, &to), amount);
+	}
+
+	burn {
+		let currency_id = T::GetStp258BenchmarkCurrencyId::get();
+		let issuer: T::AccountId = whitelisted_caller();
+		set_roles::<T>(currency_id, &issuer);
+		let amount = dollar::<T>(currency_id).saturating_mul(1_000u32.into());
+		let who: T::AccountId = account("who", 0, SEED);
+		set_balance::<T>(currency_id, &who, amount);
+		let who_lookup = T::Lookup::unlookup(who.clone());
+	}: _(RawOrigin::Signed(issuer), currency_id, who_lookup, amount)
+	verify {
+		assert_eq!(<Pallet<T> as Stp258Currency<T::AccountId>>::total_balance(currency_id, &who), Zero::zero());
+	}
+
+	set_max_supply {
+		let currency_id = T::GetStp258BenchmarkCurrencyId::get();
+		register::<T>(currency_id);
+		let max_supply = dollar::<T>(currency_id).saturating_mul(1_000u32.into());
+		let call = Call::<T>::set_max_supply(currency_id, Some(max_supply));
+		let origin = T::RegistryOrigin::successful_origin();
+	}: { call.dispatch_bypass_filter(origin)? }
+	verify {
+		assert_eq!(Pallet::<T>::max_supply(currency_id), Some(max_supply));
+	}
+
+	set_issuance_limits {
+		let currency_id = T::GetStp258BenchmarkCurrencyId::get();
+		register::<T>(currency_id);
+		let limit = dollar::<T>(currency_id).saturating_mul(1_000u32.into());
+		let limits = IssuanceLimits { mint: limit, burn: limit };
+		let call = Call::<T>::set_issuance_limits(currency_id, Some(limits.clone()));
+		let origin = T::RegistryOrigin::successful_origin();
+	}: { call.dispatch_bypass_filter(origin)? }
+	verify {
+		assert_eq!(Pallet::<T>::issuance_limits(currency_id), Some(limits));
+	}
+
+	approve {
+		let currency_id = T::GetStp258BenchmarkCurrencyId::get();
+		register::<T>(currency_id);
+		let amount = dollar::<T>(currency_id).saturating_mul(1_000u32.into());
+		let owner: T::AccountId = whitelisted_caller();
+		let spender: T::AccountId = account("spender", 0, SEED);
+		let spender_lookup = T::Lookup::unlookup(spender.clone());
+	}: _(RawOrigin::Signed(owner.clone()), spender_lookup, currency_id, amount)
+	verify {
+		assert_eq!(Pallet::<T>::allowance((currency_id, &owner), &spender), amount);
+	}
+
+	increase_allowance {
+		let currency_id = T::GetStp258BenchmarkCurrencyId::get();
+		register::<T>(currency_id);
+		let amount = dollar::<T>(currency_id).saturating_mul(1_000u32.into());
+		let owner: T::AccountId = whitelisted_caller();
+		let spender: T::AccountId = account("spender", 0, SEED);
+		Allowances::<T>::insert((currency_id, &owner), &spender, amount);
+		let spender_lookup = T::Lookup::unlookup(spender.clone());
+	}: _(RawOrigin::Signed(owner.clone()), spender_lookup, currency_id, amount)
+	verify {
+		assert_eq!(Pallet::<T>::allowance((currency_id, &owner), &spender), amount.saturating_add(amount));
+	}
+
+	decrease_allowance {
+		let currency_id = T::GetStp258BenchmarkCurrencyId::get();
+		let amount = dollar::<T>(currency_id).saturating_mul(1_000u32.into());
+		let owner: T::AccountId = whitelisted_caller();
+		let spender: T::AccountId = account("spender", 0, SEED);
+		Allowances::<T>::insert((currency_id, &owner), &spender, amount.saturating_add(amount));
+		let spender_lookup = T::Lookup::unlookup(spender.clone());
+	}: _(RawOrigin::Signed(owner.clone()), spender_lookup, currency_id, amount)
+	verify {
+		assert_eq!(Pallet::<T>::allowance((currency_id, &owner), &spender), amount);
+	}
+
+	transfer_from {
+		let currency_id = T::GetStp258BenchmarkCurrencyId::get();
+		register::<T>(currency_id);
+		let amount = dollar::<T>(currency_id).saturating_mul(1_000u32.into());
+		let owner: T::AccountId = account("owner", 0, SEED);
+		set_balance::<T>(currency_id, &owner, amount);
+		let spender: T::AccountId = whitelisted_caller();
+		Allowances::<T>::insert((currency_id, &owner), &spender, amount);
+		let owner_lookup = T::Lookup::unlookup(owner.clone());
+		let to: T::AccountId = account("to", 0, SEED);
+		let to_lookup = T::Lookup::unlookup(to.clone());
+	}: _(RawOrigin::Signed(spender.clone()), owner_lookup, to_lookup, currency_id, amount)
+	verify {
+		assert_eq!(<Pallet<T> as Stp258Currency<T::AccountId>>::total_balance(currency_id, &to), amount);
+		assert_eq!(Pallet::<T>::allowance((currency_id, &owner), &spender), Zero::zero());
+	}
+
+	set_fee_currency {
+		let currency_id = T::GetStp258BenchmarkCurrencyId::get();
+		register::<T>(currency_id);
+		let who: T::AccountId = whitelisted_caller();
+	}: _(RawOrigin::Signed(who.clone()), Some(currency_id))
+	verify {
+		assert_eq!(Pallet::<T>::fee_currency(&who), Some(currency_id));
+	}
+
+	transfer_converted {
+		let from_currency_id = T::GetStp258BenchmarkCurrencyId::get();
+		let to_currency_id = T::GetStp258NativeId::get();
+		register::<T>(from_currency_id);
+		register::<T>(to_currency_id);
+		let amount = dollar::<T>(from_currency_id).saturating_mul(1_000u32.into());
+		let from: T::AccountId = whitelisted_caller();
+		set_balance::<T>(from_currency_id, &from, amount);
+		let received = Pallet::<T>::convert(from_currency_id, to_currency_id, amount).unwrap_or_default();
+		set_balance::<T>(to_currency_id, &T::LiquidityAccount::get(), received.saturating_mul(2u32.into()));
+
+		let to: T::AccountId = account("to", 0, SEED);
+		let to_lookup = T::Lookup::unlookup(to.clone());
+	}: _(RawOrigin::Signed(from), to_lookup, from_currency_id, to_currency_id, amount, received)
+	verify {
+		assert_eq!(T::Stp258Native::total_balance(&to), received);
+	}
+}
+
+impl_benchmark_test_suite!(
+	Pallet,
+	crate::mock::ExtBuilder::default().build(),
+	crate::mock::Runtime,
+);
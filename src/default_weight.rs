@@ -1,4 +1,13 @@
-//! THIS FILE WAS AUTO-GENERATED USING THE SUBSTRATE BENCHMARK CLI VERSION 2.0.0
+//! Weights for the Stp258 currencies module.
+//!
+//! None of these weights were generated for the module as it stands. The
+//! base figures of the transfer and `update_balance` weights were generated
+//! using the Substrate benchmark CLI version 2.0.0 before the registry,
+//! pause, freeze, reason lock, supply cap and issuance limit checks were
+//! added, and the storage reads and writes of those checks are added by
+//! hand. The other base figures are round placeholders.
+//!
+//! Regenerate this file from `benchmarking.rs` before relying on it.
 
 #![allow(unused_parens)]
 #![allow(unused_imports)]
@@ -9,21 +18,156 @@ use frame_support::weights::{constants::RocksDbWeight as DbWeight, Weight};
 impl crate::WeightInfo for () {
 	fn transfer_non_native_currency() -> Weight {
 		(172_011_000 as Weight)
-			.saturating_add(DbWeight::get().reads(5 as Weight))
+			.saturating_add(DbWeight::get().reads(9 as Weight))
 			.saturating_add(DbWeight::get().writes(2 as Weight))
 	}
 	fn transfer_native_currency() -> Weight {
 		(43_023_000 as Weight)
+			.saturating_add(DbWeight::get().reads(3 as Weight))
 	}
 	fn update_balance_non_native_currency() -> Weight {
 		(137_440_000 as Weight)
-			.saturating_add(DbWeight::get().reads(5 as Weight))
-			.saturating_add(DbWeight::get().writes(2 as Weight))
+			.saturating_add(DbWeight::get().reads(10 as Weight))
+			.saturating_add(DbWeight::get().writes(3 as Weight))
 	}
 	fn update_balance_native_currency_creating() -> Weight {
 		(64_432_000 as Weight)
+			.saturating_add(DbWeight::get().reads(5 as Weight))
+			.saturating_add(DbWeight::get().writes(1 as Weight))
 	}
 	fn update_balance_native_currency_killing() -> Weight {
 		(62_595_000 as Weight)
+			.saturating_add(DbWeight::get().reads(5 as Weight))
+			.saturating_add(DbWeight::get().writes(1 as Weight))
+	}
+	// Placeholders, not benchmarked yet.
+	fn transfer_keep_alive() -> Weight {
+		(180_000_000 as Weight)
+			.saturating_add(DbWeight::get().reads(9 as Weight))
+			.saturating_add(DbWeight::get().writes(2 as Weight))
+	}
+	fn transfer_all() -> Weight {
+		(1_000_000_000 as Weight)
+			.saturating_add(DbWeight::get().reads(394 as Weight))
+			.saturating_add(DbWeight::get().writes(2 as Weight))
+	}
+	fn transfer_batch(n: u32) -> Weight {
+		(10_000_000 as Weight)
+			.saturating_add((180_000_000 as Weight).saturating_mul(n as Weight))
+			.saturating_add(DbWeight::get().reads((9 as Weight).saturating_mul(n as Weight)))
+			.saturating_add(DbWeight::get().writes((2 as Weight).saturating_mul(n as Weight)))
+	}
+	fn expire_locks(n: u32) -> Weight {
+		(10_000_000 as Weight)
+			.saturating_add((50_000_000 as Weight).saturating_mul(n as Weight))
+			.saturating_add(DbWeight::get().reads(1 as Weight))
+			.saturating_add(DbWeight::get().reads((3 as Weight).saturating_mul(n as Weight)))
+			.saturating_add(DbWeight::get().writes(1 as Weight))
+			.saturating_add(DbWeight::get().writes((5 as Weight).saturating_mul(n as Weight)))
+	}
+	fn vested_transfer() -> Weight {
+		(150_000_000 as Weight)
+			.saturating_add(DbWeight::get().reads(13 as Weight))
+			.saturating_add(DbWeight::get().writes(8 as Weight))
+	}
+	fn claim(i: u32) -> Weight {
+		(60_000_000 as Weight)
+			.saturating_add((1_000_000 as Weight).saturating_mul(i as Weight))
+			.saturating_add(DbWeight::get().reads(4 as Weight))
+			.saturating_add(DbWeight::get().writes(6 as Weight))
+	}
+	fn update_vesting_schedules(i: u32) -> Weight {
+		(60_000_000 as Weight)
+			.saturating_add((1_000_000 as Weight).saturating_mul(i as Weight))
+			.saturating_add(DbWeight::get().reads(4 as Weight))
+			.saturating_add(DbWeight::get().writes(6 as Weight))
+	}
+	fn register_currency() -> Weight {
+		(25_000_000 as Weight)
+			.saturating_add(DbWeight::get().reads(1 as Weight))
+			.saturating_add(DbWeight::get().writes(1 as Weight))
+	}
+	fn update_currency() -> Weight {
+		(25_000_000 as Weight)
+			.saturating_add(DbWeight::get().reads(1 as Weight))
+			.saturating_add(DbWeight::get().writes(1 as Weight))
+	}
+	fn pause_currency() -> Weight {
+		(20_000_000 as Weight)
+			.saturating_add(DbWeight::get().writes(1 as Weight))
+	}
+	fn unpause_currency() -> Weight {
+		(20_000_000 as Weight)
+			.saturating_add(DbWeight::get().writes(1 as Weight))
+	}
+	fn freeze_account() -> Weight {
+		(20_000_000 as Weight)
+			.saturating_add(DbWeight::get().reads(1 as Weight))
+			.saturating_add(DbWeight::get().writes(1 as Weight))
+	}
+	fn thaw_account() -> Weight {
+		(20_000_000 as Weight)
+			.saturating_add(DbWeight::get().reads(1 as Weight))
+			.saturating_add(DbWeight::get().writes(1 as Weight))
+	}
+	fn set_roles() -> Weight {
+		(30_000_000 as Weight)
+			.saturating_add(DbWeight::get().reads(1 as Weight))
+			.saturating_add(DbWeight::get().writes(1 as Weight))
+	}
+	fn transfer_roles() -> Weight {
+		(30_000_000 as Weight)
+			.saturating_add(DbWeight::get().reads(1 as Weight))
+			.saturating_add(DbWeight::get().writes(1 as Weight))
+	}
+	fn mint() -> Weight {
+		(150_000_000 as Weight)
+			.saturating_add(DbWeight::get().reads(8 as Weight))
+			.saturating_add(DbWeight::get().writes(3 as Weight))
+	}
+	fn burn() -> Weight {
+		(150_000_000 as Weight)
+			.saturating_add(DbWeight::get().reads(9 as Weight))
+			.saturating_add(DbWeight::get().writes(3 as Weight))
+	}
+	fn set_max_supply() -> Weight {
+		(20_000_000 as Weight)
+			.saturating_add(DbWeight::get().reads(1 as Weight))
+			.saturating_add(DbWeight::get().writes(1 as Weight))
+	}
+	fn set_issuance_limits() -> Weight {
+		(20_000_000 as Weight)
+			.saturating_add(DbWeight::get().reads(1 as Weight))
+			.saturating_add(DbWeight::get().writes(1 as Weight))
+	}
+	fn approve() -> Weight {
+		(20_000_000 as Weight)
+			.saturating_add(DbWeight::get().reads(1 as Weight))
+			.saturating_add(DbWeight::get().writes(1 as Weight))
+	}
+	fn increase_allowance() -> Weight {
+		(25_000_000 as Weight)
+			.saturating_add(DbWeight::get().reads(2 as Weight))
+			.saturating_add(DbWeight::get().writes(1 as Weight))
+	}
+	fn decrease_allowance() -> Weight {
+		(25_000_000 as Weight)
+			.saturating_add(DbWeight::get().reads(1 as Weight))
+			.saturating_add(DbWeight::get().writes(1 as Weight))
+	}
+	fn transfer_from() -> Weight {
+		(200_000_000 as Weight)
+			.saturating_add(DbWeight::get().reads(10 as Weight))
+			.saturating_add(DbWeight::get().writes(3 as Weight))
+	}
+	fn set_fee_currency() -> Weight {
+		(20_000_000 as Weight)
+			.saturating_add(DbWeight::get().reads(1 as Weight))
+			.saturating_add(DbWeight::get().writes(1 as Weight))
+	}
+	fn transfer_converted() -> Weight {
+		(350_000_000 as Weight)
+			.saturating_add(DbWeight::get().reads(18 as Weight))
+			.saturating_add(DbWeight::get().writes(4 as Weight))
 	}
 }
@@ -0,0 +1,95 @@
+//! Transaction fees payable in any Stp258 currency.
+
+use crate::{BalanceOf, Config, CurrencyIdOf, Event, Pallet};
+use frame_support::traits::{Get, WithdrawReasons};
+use pallet_transaction_payment::OnChargeTransaction;
+use sp_runtime::{
+	traits::{DispatchInfoOf, PostDispatchInfoOf, Saturating, Zero},
+	transaction_validity::{InvalidTransaction, TransactionValidityError},
+};
+use sp_std::marker::PhantomData;
+
+/// Charges transaction fees in the fee currency each account picked with
+/// `set_fee_currency`, or in the native currency if it picked none.
+///
+/// Fees are converted out of the native currency at the prices of
+/// `T::PriceProvider`, rounding up. If the fee currency is paused, frozen
+/// for the payer or has no usable price, the fee is charged in the native
+/// currency instead, so that the payer can still pick another one.
+///
+/// Fees are withdrawn from the payer like
+/// `pallet_transaction_payment::CurrencyAdapter` does: only locks on
+/// transaction payments and tips apply, and the payer is kept alive. Unused
+/// fees are refunded in the same currency, and the fees charged, tips
+/// included, are credited to the account `R`, e.g. a treasury.
+pub struct ChargeStp258Fee<T, R>(PhantomData<(T, R)>);
+
+impl<T: Config, R> ChargeStp258Fee<T, R> {
+	/// The amount of `currency_id` worth the native `fee`, rounded up so
+	/// that no fee becomes free.
+	fn fee_in(currency_id: CurrencyIdOf<T>, fee: BalanceOf<T>) -> Option<BalanceOf<T>> {
+		Pallet::<T>::convert_up(T::GetStp258NativeId::get(), currency_id, fee)
+			.ok()
+			.filter(|amount| fee.is_zero() || !amount.is_zero())
+	}
+
+	/// The currency `who` pays the native `fee` in, and the amount of it.
+	fn fee_payment(who: &T::AccountId, fee: BalanceOf<T>) -> (CurrencyIdOf<T>, BalanceOf<T>) {
+		Pallet::<T>::fee_currency(who)
+			.filter(|currency_id| !Pallet::<T>::is_paused(currency_id) && !Pallet::<T>::is_frozen(who, currency_id))
+			.and_then(|currency_id| Some((currency_id, Self::fee_in(currency_id, fee)?)))
+			.unwrap_or_else(|| (T::GetStp258NativeId::get(), fee))
+	}
+}
+
+impl<T, R> OnChargeTransaction<T> for ChargeStp258Fee<T, R>
+where
+	T: Config + pallet_transaction_payment::Config,
+	R: Get<T::AccountId>,
+{
+	type Balance = BalanceOf<T>;
+	/// The fee currency and the amount of it withdrawn.
+	type LiquidityInfo = Option<(CurrencyIdOf<T>, BalanceOf<T>)>;
+
+	fn withdraw_fee(
+		who: &T::AccountId,
+		_call: &T::Call,
+		_dispatch_info: &DispatchInfoOf<T::Call>,
+		fee: Self::Balance,
+		tip: Self::Balance,
+	) -> Result<Self::LiquidityInfo, TransactionValidityError> {
+		if fee.is_zero() {
+			return Ok(None);
+		}
+		let (currency_id, amount) = Self::fee_payment(who, fee);
+		let reasons = if tip.is_zero() {
+			WithdrawReasons::TRANSACTION_PAYMENT
+		} else {
+			WithdrawReasons::TRANSACTION_PAYMENT | WithdrawReasons::TIP
+		};
+		Pallet::<T>::withdraw_fee(currency_id, who, amount, reasons).map_err(|_| InvalidTransaction::Payment)?;
+		Ok(Some((currency_id, amount)))
+	}
+
+	fn correct_and_deposit_fee(
+		who: &T::AccountId,
+		_dispatch_info: &DispatchInfoOf<T::Call>,
+		_post_info: &PostDispatchInfoOf<T::Call>,
+		corrected_fee: Self::Balance,
+		_tip: Self::Balance,
+		already_withdrawn: Self::LiquidityInfo,
+	) -> Result<(), TransactionValidityError> {
+		if let Some((currency_id, paid)) = already_withdrawn {
+			// `corrected_fee` includes the tip
+			let charged = Self::fee_in(currency_id, corrected_fee).unwrap_or(paid).min(paid);
+			let refund = paid.saturating_sub(charged);
+			// deposits go straight to the backend, which only fails on
+			// overflow, where they are lost as with `CurrencyAdapter`
+			let _ = Pallet::<T>::deposit_fee(currency_id, who, refund);
+			let _ = Pallet::<T>::deposit_fee(currency_id, &R::get(), charged);
+
+			Pallet::<T>::deposit_event(Event::FeePaid(currency_id, who.clone(), charged));
+		}
+		Ok(())
+	}
+}
@@ -1,7 +1,7 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 #![allow(clippy::unused_unit)]
 
-use codec::Codec;
+use codec::{Codec, Encode};
 use frame_support::{
 	pallet_prelude::*,
 	traits::{
@@ -19,20 +19,38 @@ use serp_traits::{
 };
 use orml_utilities::with_transaction_result;
 use sp_runtime::{
-	traits::{CheckedSub, MaybeSerializeDeserialize, StaticLookup, Zero},
-	DispatchError, DispatchResult,
+	helpers_128bit::multiply_by_rational,
+	traits::{
+		CheckedAdd, CheckedMul, CheckedSub, MaybeSerializeDeserialize, One, Saturating, StaticLookup,
+		UniqueSaturatedInto, Zero,
+	},
+	DispatchError, DispatchResult, FixedPointNumber,
 };
 use sp_std::{
+	collections::btree_set::BTreeSet,
 	convert::{TryFrom, TryInto},
 	fmt::Debug,
 	marker, result,
+	vec::Vec,
 };
 
+mod benchmarking;
 mod default_weight;
+mod fee;
 mod mock;
+mod registry;
 mod tests;
+pub mod traits;
+mod vesting;
 
 pub use module::*;
+pub use fee::ChargeStp258Fee;
+pub use traits::{
+	OnDeposit, OnTransfer, OnWithdraw, PreTransfer, Price, PriceProvider, Stp258AssetNamedReservable,
+	Stp258AssetReasonLockable, Stp258CurrencyNamedReservable, Stp258CurrencyReasonLockable,
+};
+pub use registry::{CurrencyMetadata, CurrencyRoles, CurrencyStatus, IssuanceLimits};
+pub use vesting::{VestingSchedule, VESTING_LOCK_ID};
 
 #[frame_support::pallet]
 pub mod module {
@@ -44,6 +62,31 @@ pub mod module {
 		fn update_balance_non_native_currency() -> Weight;
 		fn update_balance_native_currency_creating() -> Weight;
 		fn update_balance_native_currency_killing() -> Weight;
+		fn transfer_keep_alive() -> Weight;
+		fn transfer_all() -> Weight;
+		fn transfer_batch(n: u32) -> Weight;
+		fn expire_locks(n: u32) -> Weight;
+		fn vested_transfer() -> Weight;
+		fn claim(i: u32) -> Weight;
+		fn update_vesting_schedules(i: u32) -> Weight;
+		fn register_currency() -> Weight;
+		fn update_currency() -> Weight;
+		fn pause_currency() -> Weight;
+		fn unpause_currency() -> Weight;
+		fn freeze_account() -> Weight;
+		fn thaw_account() -> Weight;
+		fn set_roles() -> Weight;
+		fn transfer_roles() -> Weight;
+		fn mint() -> Weight;
+		fn burn() -> Weight;
+		fn set_max_supply() -> Weight;
+		fn set_issuance_limits() -> Weight;
+		fn approve() -> Weight;
+		fn increase_allowance() -> Weight;
+		fn decrease_allowance() -> Weight;
+		fn transfer_from() -> Weight;
+		fn set_fee_currency() -> Weight;
+		fn transfer_converted() -> Weight;
 	}
 
 	pub(crate) type BalanceOf<T> =
@@ -52,6 +95,7 @@ pub mod module {
 		<<T as Config>::Stp258Currency as Stp258Currency<<T as frame_system::Config>::AccountId>>::CurrencyId;
 	pub(crate) type AmountOf<T> =
 		<<T as Config>::Stp258Currency as Stp258CurrencyExtended<<T as frame_system::Config>::AccountId>>::Amount;
+	pub(crate) type VestingScheduleOf<T> = VestingSchedule<<T as frame_system::Config>::BlockNumber, BalanceOf<T>>;
 
 	#[pallet::config]
 	pub trait Config: frame_system::Config {
@@ -63,12 +107,76 @@ pub mod module {
 			+ Stp258CurrencyReservable<Self::AccountId>;
 
 		type Stp258Native: Stp258AssetExtended<Self::AccountId, Balance = BalanceOf<Self>, Amount = AmountOf<Self>>
-			+ Stp258AssetLockable<Self::AccountId, Balance = BalanceOf<Self>>
+			+ Stp258AssetReasonLockable<Self::AccountId, Balance = BalanceOf<Self>>
 			+ Stp258AssetReservable<Self::AccountId, Balance = BalanceOf<Self>>;
 
 		#[pallet::constant]
 		type GetStp258NativeId: Get<CurrencyIdOf<Self>>;
 
+		/// The size of one whole unit of the native currency.
+		#[pallet::constant]
+		type GetStp258NativeBaseUnit: Get<BalanceOf<Self>>;
+
+		/// The non-native currency used to benchmark the non-native
+		/// dispatch paths.
+		#[cfg(feature = "runtime-benchmarks")]
+		type GetStp258BenchmarkCurrencyId: Get<CurrencyIdOf<Self>>;
+
+		/// The identifier of a named reserve.
+		type ReserveIdentifier: Parameter + Member + Copy;
+
+		/// The maximum number of locks that can expire in one block.
+		#[pallet::constant]
+		type MaxExpiringLocksPerBlock: Get<u32>;
+
+		/// The maximum number of vesting schedules per account and
+		/// currency.
+		#[pallet::constant]
+		type MaxVestingSchedules: Get<u32>;
+
+		/// The maximum number of transfers in one `transfer_batch`.
+		#[pallet::constant]
+		type MaxBatchTransfers: Get<u32>;
+
+		/// The number of blocks over which minted and burned amounts are
+		/// counted against `IssuanceLimits`. Zero never forgets them.
+		#[pallet::constant]
+		type IssuanceWindow: Get<Self::BlockNumber>;
+
+		/// Checked before every transfer, may veto it.
+		type PreTransfer: PreTransfer<Self::AccountId, CurrencyIdOf<Self>, BalanceOf<Self>>;
+
+		/// Notified after every transfer.
+		type OnTransfer: OnTransfer<Self::AccountId, CurrencyIdOf<Self>, BalanceOf<Self>>;
+
+		/// Notified after every deposit, including positive balance updates.
+		type OnDeposit: OnDeposit<Self::AccountId, CurrencyIdOf<Self>, BalanceOf<Self>>;
+
+		/// Notified after every withdrawal, including negative balance
+		/// updates.
+		type OnWithdraw: OnWithdraw<Self::AccountId, CurrencyIdOf<Self>, BalanceOf<Self>>;
+
+		/// The prices used to convert between currencies.
+		type PriceProvider: PriceProvider<CurrencyIdOf<Self>>;
+
+		/// The account which takes one currency and pays out the other in
+		/// `transfer_converted`.
+		type LiquidityAccount: Get<Self::AccountId>;
+
+		/// The origin which may update balances through `update_balance`.
+		type UpdateOrigin: EnsureOrigin<Self::Origin>;
+
+		/// The origin which may register currencies, update their metadata
+		/// and appoint their roles.
+		type RegistryOrigin: EnsureOrigin<Self::Origin>;
+
+		/// The origin which may pause and unpause movements of a currency.
+		type PauseOrigin: EnsureOrigin<Self::Origin>;
+
+		/// The origin which may freeze and thaw accounts, besides the
+		/// freezer of the currency.
+		type FreezeOrigin: EnsureOrigin<Self::Origin>;
+
 		/// Weight information for extrinsics in this module.
 		type WeightInfo: WeightInfo;
 	}
@@ -79,6 +187,55 @@ pub mod module {
 		AmountIntoBalanceFailed,
 		/// Balance is too low.
 		BalanceTooLow,
+		/// Failed because liquidity restrictions due to locking.
+		LiquidityRestrictions,
+		/// The lock expiry is not in the future.
+		LockExpiryInPast,
+		/// Too many locks already expire in that block.
+		TooManyExpiringLocks,
+		/// Vesting period is zero.
+		ZeroVestingPeriod,
+		/// Number of vests is zero.
+		ZeroVestingPeriodCount,
+		/// Arithmetic calculation overflow.
+		NumOverflow,
+		/// Insufficient amount of balance to lock.
+		InsufficientBalanceToLock,
+		/// This account has too many vesting schedules for the currency.
+		TooManyVestingSchedules,
+		/// The currency is not registered.
+		CurrencyNotRegistered,
+		/// The currency is already registered.
+		CurrencyAlreadyRegistered,
+		/// The currency is deprecated and cannot be issued.
+		CurrencyDeprecated,
+		/// The currency is paused.
+		CurrencyPaused,
+		/// The account is frozen for the currency.
+		AccountFrozen,
+		/// No roles are set for the currency.
+		RolesNotSet,
+		/// The caller does not hold the required role.
+		NoPermission,
+		/// Issuance would exceed the maximum supply of the currency.
+		SupplyCapExceeded,
+		/// Issuance would exceed the mint limit of the current window.
+		MintLimitExceeded,
+		/// Destruction would exceed the burn limit of the current window.
+		BurnLimitExceeded,
+		/// The spender is not allowed to spend this much of the owner's
+		/// balance.
+		InsufficientAllowance,
+		/// The batch has more than `MaxBatchTransfers` transfers.
+		TooManyBatchTransfers,
+		/// The currencies have no price to convert between them.
+		PriceUnavailable,
+		/// The converted amount does not fit in a balance.
+		ConversionOverflow,
+		/// Transfer would kill the sender's account.
+		KeepAlive,
+		/// The converted amount is less than the sender accepts.
+		ReceivedTooLittle,
 	}
 
 	#[pallet::event]
@@ -92,13 +249,308 @@ pub mod module {
 		Deposited(CurrencyIdOf<T>, T::AccountId, BalanceOf<T>),
 		/// Withdraw success. [currency_id, who, amount]
 		Withdrawn(CurrencyIdOf<T>, T::AccountId, BalanceOf<T>),
+		/// Lock set or extended. [lock_id, currency_id, who, amount]
+		LockSet(LockIdentifier, CurrencyIdOf<T>, T::AccountId, BalanceOf<T>),
+		/// Lock removed. [lock_id, currency_id, who]
+		LockRemoved(LockIdentifier, CurrencyIdOf<T>, T::AccountId),
+		/// Some balance was reserved. [currency_id, who, amount]
+		Reserved(CurrencyIdOf<T>, T::AccountId, BalanceOf<T>),
+		/// Some balance was unreserved. [currency_id, who, amount]
+		Unreserved(CurrencyIdOf<T>, T::AccountId, BalanceOf<T>),
+		/// Some balance was slashed. [currency_id, who, free_amount, reserved_amount]
+		Slashed(CurrencyIdOf<T>, T::AccountId, BalanceOf<T>, BalanceOf<T>),
+		/// Some reserved balance was moved to another account. [currency_id,
+		/// from, to, amount, status]
+		ReserveRepatriated(CurrencyIdOf<T>, T::AccountId, T::AccountId, BalanceOf<T>, BalanceStatus),
+		/// Added new vesting schedule. [currency_id, from, to, vesting_schedule]
+		VestingScheduleAdded(CurrencyIdOf<T>, T::AccountId, T::AccountId, VestingScheduleOf<T>),
+		/// Claimed vesting. [currency_id, who, locked_amount]
+		Claimed(CurrencyIdOf<T>, T::AccountId, BalanceOf<T>),
+		/// Updated vesting schedules. [currency_id, who]
+		VestingSchedulesUpdated(CurrencyIdOf<T>, T::AccountId),
+		/// Currency registered. [currency_id, metadata]
+		CurrencyRegistered(CurrencyIdOf<T>, CurrencyMetadata),
+		/// Currency metadata updated. [currency_id, metadata]
+		CurrencyUpdated(CurrencyIdOf<T>, CurrencyMetadata),
+		/// Currency paused. [currency_id]
+		CurrencyPaused(CurrencyIdOf<T>),
+		/// Currency unpaused. [currency_id]
+		CurrencyUnpaused(CurrencyIdOf<T>),
+		/// Account frozen for a currency. [currency_id, who]
+		AccountFrozen(CurrencyIdOf<T>, T::AccountId),
+		/// Account thawed for a currency. [currency_id, who]
+		AccountThawed(CurrencyIdOf<T>, T::AccountId),
+		/// Currency roles set. [currency_id, issuer, freezer, admin]
+		RolesSet(CurrencyIdOf<T>, T::AccountId, T::AccountId, T::AccountId),
+		/// Maximum supply set, `None` if uncapped. [currency_id, max_supply]
+		MaxSupplySet(CurrencyIdOf<T>, Option<BalanceOf<T>>),
+		/// Issuance limits set, `None` if unlimited. [currency_id, limits]
+		IssuanceLimitsSet(CurrencyIdOf<T>, Option<IssuanceLimits<BalanceOf<T>>>),
+		/// Allowance changed. [currency_id, owner, spender, allowance]
+		Approval(CurrencyIdOf<T>, T::AccountId, T::AccountId, BalanceOf<T>),
+		/// Fee currency chosen, `None` for the native currency. [who,
+		/// currency_id]
+		FeeCurrencySet(T::AccountId, Option<CurrencyIdOf<T>>),
+		/// Transaction fee paid. [currency_id, who, amount]
+		FeePaid(CurrencyIdOf<T>, T::AccountId, BalanceOf<T>),
+		/// Transfer converted into another currency. [from_currency_id,
+		/// to_currency_id, from, to, amount, received]
+		TransferredConverted(
+			CurrencyIdOf<T>,
+			CurrencyIdOf<T>,
+			T::AccountId,
+			T::AccountId,
+			BalanceOf<T>,
+			BalanceOf<T>,
+		),
+	}
+
+	/// The amount reserved under each name, per account and currency.
+	///
+	/// ReservesNamed: map (AccountId, CurrencyId), ReserveIdentifier =>
+	/// Balance
+	#[pallet::storage]
+	#[pallet::getter(fn reserves_named)]
+	pub type ReservesNamed<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		(T::AccountId, CurrencyIdOf<T>),
+		Twox64Concat,
+		T::ReserveIdentifier,
+		BalanceOf<T>,
+		ValueQuery,
+	>;
+
+	/// Locks on non-native currencies that only restrict some withdraw
+	/// reasons. Locks restricting all reasons are kept by
+	/// `T::Stp258Currency` itself.
+	///
+	/// ReasonLocks: map (AccountId, CurrencyId), LockIdentifier =>
+	/// Option<(Balance, WithdrawReasons)>
+	#[pallet::storage]
+	#[pallet::getter(fn reason_locks)]
+	pub type ReasonLocks<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		(T::AccountId, CurrencyIdOf<T>),
+		Blake2_128Concat,
+		LockIdentifier,
+		(BalanceOf<T>, WithdrawReasons),
+		OptionQuery,
+	>;
+
+	/// The amount of every lock set through this module, whichever
+	/// currency and withdraw reasons it is on.
+	///
+	/// Locks: map (AccountId, CurrencyId), LockIdentifier => Option<Balance>
+	#[pallet::storage]
+	pub type Locks<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		(T::AccountId, CurrencyIdOf<T>),
+		Blake2_128Concat,
+		LockIdentifier,
+		BalanceOf<T>,
+		OptionQuery,
+	>;
+
+	/// The locks to be removed at each block.
+	///
+	/// ExpiringLocks: map BlockNumber => Vec<(AccountId, CurrencyId,
+	/// LockIdentifier)>
+	#[pallet::storage]
+	#[pallet::getter(fn expiring_locks)]
+	pub type ExpiringLocks<T: Config> =
+		StorageMap<_, Twox64Concat, T::BlockNumber, Vec<(T::AccountId, CurrencyIdOf<T>, LockIdentifier)>, ValueQuery>;
+
+	/// The block at which a lock expires. Setting or removing the lock by
+	/// other means clears it.
+	///
+	/// LockExpiries: map (AccountId, CurrencyId), LockIdentifier =>
+	/// Option<BlockNumber>
+	#[pallet::storage]
+	#[pallet::getter(fn lock_expiries)]
+	pub type LockExpiries<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		(T::AccountId, CurrencyIdOf<T>),
+		Blake2_128Concat,
+		LockIdentifier,
+		T::BlockNumber,
+		OptionQuery,
+	>;
+
+	/// Vesting schedules of an account per currency.
+	///
+	/// VestingSchedules: double_map AccountId, CurrencyId =>
+	/// Vec<VestingSchedule>
+	#[pallet::storage]
+	#[pallet::getter(fn vesting_schedules)]
+	pub type VestingSchedules<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		T::AccountId,
+		Twox64Concat,
+		CurrencyIdOf<T>,
+		Vec<VestingScheduleOf<T>>,
+		ValueQuery,
+	>;
+
+	/// The metadata of every registered currency.
+	///
+	/// CurrencyRegistry: map CurrencyId => Option<CurrencyMetadata>
+	#[pallet::storage]
+	#[pallet::getter(fn currency_metadata)]
+	pub type CurrencyRegistry<T: Config> = StorageMap<_, Twox64Concat, CurrencyIdOf<T>, CurrencyMetadata, OptionQuery>;
+
+	/// The currencies whose movements are paused.
+	///
+	/// PausedCurrencies: map CurrencyId => bool
+	#[pallet::storage]
+	#[pallet::getter(fn is_paused)]
+	pub type PausedCurrencies<T: Config> = StorageMap<_, Twox64Concat, CurrencyIdOf<T>, bool, ValueQuery>;
+
+	/// The accounts whose balance of a currency cannot leave the account.
+	///
+	/// FrozenAccounts: double_map AccountId, CurrencyId => bool
+	#[pallet::storage]
+	#[pallet::getter(fn is_frozen)]
+	pub type FrozenAccounts<T: Config> =
+		StorageDoubleMap<_, Blake2_128Concat, T::AccountId, Twox64Concat, CurrencyIdOf<T>, bool, ValueQuery>;
+
+	/// The issuer, freezer and admin of each currency.
+	///
+	/// Roles: map CurrencyId => Option<CurrencyRoles>
+	#[pallet::storage]
+	#[pallet::getter(fn roles)]
+	pub type Roles<T: Config> =
+		StorageMap<_, Twox64Concat, CurrencyIdOf<T>, CurrencyRoles<T::AccountId>, OptionQuery>;
+
+	/// The maximum total issuance of each capped currency.
+	///
+	/// MaxSupply: map CurrencyId => Option<Balance>
+	#[pallet::storage]
+	#[pallet::getter(fn max_supply)]
+	pub type MaxSupply<T: Config> = StorageMap<_, Twox64Concat, CurrencyIdOf<T>, BalanceOf<T>, OptionQuery>;
+
+	/// The most of each limited currency that can be minted and burned per
+	/// issuance window.
+	///
+	/// IssuanceLimitsOf: map CurrencyId => Option<IssuanceLimits>
+	#[pallet::storage]
+	#[pallet::getter(fn issuance_limits)]
+	pub type IssuanceLimitsOf<T: Config> =
+		StorageMap<_, Twox64Concat, CurrencyIdOf<T>, IssuanceLimits<BalanceOf<T>>, OptionQuery>;
+
+	/// The amounts of each limited currency minted and burned per block
+	/// within the last issuance window, oldest first.
+	///
+	/// WindowIssuance: map CurrencyId => Vec<(BlockNumber, Balance, Balance)>
+	#[pallet::storage]
+	#[pallet::getter(fn window_issuance)]
+	pub type WindowIssuance<T: Config> =
+		StorageMap<_, Twox64Concat, CurrencyIdOf<T>, Vec<(T::BlockNumber, BalanceOf<T>, BalanceOf<T>)>, ValueQuery>;
+
+	/// The amount each spender may transfer out of an owner's balance.
+	///
+	/// Allowances: double_map (CurrencyId, AccountId), AccountId => Balance
+	#[pallet::storage]
+	#[pallet::getter(fn allowance)]
+	pub type Allowances<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		(CurrencyIdOf<T>, T::AccountId),
+		Blake2_128Concat,
+		T::AccountId,
+		BalanceOf<T>,
+		ValueQuery,
+	>;
+
+	/// The currency each account pays its transaction fees in, if not the
+	/// native currency.
+	///
+	/// FeeCurrencies: map AccountId => Option<CurrencyId>
+	#[pallet::storage]
+	#[pallet::getter(fn fee_currency)]
+	pub type FeeCurrencies<T: Config> = StorageMap<_, Blake2_128Concat, T::AccountId, CurrencyIdOf<T>, OptionQuery>;
+
+	#[pallet::genesis_config]
+	pub struct GenesisConfig<T: Config> {
+		/// The currencies to register, with their metadata.
+		pub currencies: Vec<(CurrencyIdOf<T>, CurrencyMetadata)>,
+		/// The initial balances, native and non-native alike, at most one
+		/// per account and currency and each at least the existential
+		/// deposit.
+		pub balances: Vec<(T::AccountId, CurrencyIdOf<T>, BalanceOf<T>)>,
+		/// The amounts to reserve out of the initial balances.
+		pub reserves: Vec<(T::AccountId, CurrencyIdOf<T>, BalanceOf<T>)>,
+		/// The locks to set on the initial balances.
+		pub locks: Vec<(T::AccountId, CurrencyIdOf<T>, LockIdentifier, BalanceOf<T>)>,
+	}
+
+	#[cfg(feature = "std")]
+	impl<T: Config> Default for GenesisConfig<T> {
+		fn default() -> Self {
+			GenesisConfig {
+				currencies: vec![],
+				balances: vec![],
+				reserves: vec![],
+				locks: vec![],
+			}
+		}
+	}
+
+	#[pallet::genesis_build]
+	impl<T: Config> GenesisBuild<T> for GenesisConfig<T> {
+		fn build(&self) {
+			for (currency_id, metadata) in &self.currencies {
+				Pallet::<T>::do_set_currency_metadata(*currency_id, metadata.clone());
+			}
+			let endowed: BTreeSet<_> = self
+				.balances
+				.iter()
+				.map(|(who, currency_id, _)| (who, currency_id).encode())
+				.collect();
+			assert!(endowed.len() == self.balances.len(), "duplicate balances in genesis.");
+			for (who, currency_id, balance) in &self.balances {
+				assert!(
+					*balance >= <Pallet<T> as Stp258Currency<T::AccountId>>::minimum_balance(*currency_id),
+					"the balance of any account should always be at least the existential deposit."
+				);
+				let amount: AmountOf<T> = (*balance)
+					.try_into()
+					.unwrap_or_else(|_| panic!("genesis balance does not fit in an amount"));
+				<Pallet<T> as Stp258CurrencyExtended<T::AccountId>>::update_balance(*currency_id, who, amount)
+					.expect("genesis balance can be set");
+			}
+			for (who, currency_id, amount) in &self.reserves {
+				<Pallet<T> as Stp258CurrencyReservable<T::AccountId>>::reserve(*currency_id, who, *amount)
+					.expect("genesis reserve is covered by the genesis balance");
+			}
+			for (who, currency_id, lock_id, amount) in &self.locks {
+				<Pallet<T> as Stp258CurrencyLockable<T::AccountId>>::set_lock(*lock_id, *currency_id, who, *amount)
+					.expect("genesis lock can be set");
+			}
+		}
 	}
 
 	#[pallet::pallet]
 	pub struct Pallet<T>(PhantomData<T>);
 
 	#[pallet::hooks]
-	impl<T: Config> Hooks<T::BlockNumber> for Pallet<T> {}
+	impl<T: Config> Hooks<T::BlockNumber> for Pallet<T> {
+		fn on_initialize(now: T::BlockNumber) -> Weight {
+			let expiring = ExpiringLocks::<T>::take(now);
+			let count = expiring.len() as u32;
+			for (who, currency_id, lock_id) in expiring {
+				// skip locks that were set again or removed since
+				if Self::lock_expiries((&who, currency_id), lock_id) == Some(now) {
+					let _ = <Self as Stp258CurrencyLockable<T::AccountId>>::remove_lock(lock_id, currency_id, &who);
+				}
+			}
+			T::WeightInfo::expire_locks(count)
+		}
+	}
 
 	#[pallet::call]
 	impl<T: Config> Pallet<T> {
@@ -106,6 +558,9 @@ pub mod module {
 		///
 		/// The dispatch origin for this call must be `Signed` by the
 		/// transactor.
+		///
+		/// A zero amount or a transfer to self only reads the currency
+		/// registry, so the rest of its weight is refunded.
 		#[pallet::weight(T::WeightInfo::transfer_non_native_currency())]
 		pub fn transfer(
 			origin: OriginFor<T>,
@@ -115,44 +570,1110 @@ pub mod module {
 		) -> DispatchResultWithPostInfo {
 			let from = ensure_signed(origin)?;
 			let to = T::Lookup::lookup(dest)?;
+			Self::ensure_registered(currency_id)?;
+			if amount.is_zero() || from == to {
+				return Ok(Some(T::DbWeight::get().reads(1)).into());
+			}
+			<Self as Stp258Currency<T::AccountId>>::transfer(currency_id, &from, &to, amount)?;
+			Ok(().into())
+		}
+
+		/// Transfer some native currency to another account.
+		///
+		/// The dispatch origin for this call must be `Signed` by the
+		/// transactor.
+		///
+		/// A zero amount or a transfer to self only reads the currency
+		/// registry, so the rest of its weight is refunded.
+		#[pallet::weight(T::WeightInfo::transfer_native_currency())]
+		pub fn transfer_native_currency(
+			origin: OriginFor<T>,
+			dest: <T::Lookup as StaticLookup>::Source,
+			#[pallet::compact] amount: BalanceOf<T>,
+		) -> DispatchResultWithPostInfo {
+			let from = ensure_signed(origin)?;
+			let to = T::Lookup::lookup(dest)?;
+			Self::ensure_registered(T::GetStp258NativeId::get())?;
+			if amount.is_zero() || from == to {
+				return Ok(Some(T::DbWeight::get().reads(1)).into());
+			}
+			<Self as Stp258Currency<T::AccountId>>::transfer(T::GetStp258NativeId::get(), &from, &to, amount)?;
+			Ok(().into())
+		}
+
+		/// Transfer some balance to another account under `currency_id`,
+		/// refusing to reap the sender.
+		///
+		/// The dispatch origin for this call must be `Signed` by the
+		/// transactor.
+		#[pallet::weight(T::WeightInfo::transfer_keep_alive())]
+		pub fn transfer_keep_alive(
+			origin: OriginFor<T>,
+			dest: <T::Lookup as StaticLookup>::Source,
+			currency_id: CurrencyIdOf<T>,
+			#[pallet::compact] amount: BalanceOf<T>,
+		) -> DispatchResultWithPostInfo {
+			let from = ensure_signed(origin)?;
+			let to = T::Lookup::lookup(dest)?;
+			Self::ensure_registered(currency_id)?;
+			if amount.is_zero() || from == to {
+				return Ok(Some(T::DbWeight::get().reads(1)).into());
+			}
+			Self::ensure_keep_alive(currency_id, &from, amount)?;
+			<Self as Stp258Currency<T::AccountId>>::transfer(currency_id, &from, &to, amount)?;
+			Ok(().into())
+		}
+
+		/// Transfer all the free balance of `currency_id` that is not locked
+		/// against transfers to another account.
+		///
+		/// If `keep_alive` is set, enough balance is left behind to keep the
+		/// sender alive.
+		///
+		/// The dispatch origin for this call must be `Signed` by the
+		/// transactor.
+		///
+		/// The weight covers the longest search `reducible_balance` can make,
+		/// and is charged in full even if nothing is transferred.
+		#[pallet::weight(T::WeightInfo::transfer_all())]
+		pub fn transfer_all(
+			origin: OriginFor<T>,
+			dest: <T::Lookup as StaticLookup>::Source,
+			currency_id: CurrencyIdOf<T>,
+			keep_alive: bool,
+		) -> DispatchResultWithPostInfo {
+			let from = ensure_signed(origin)?;
+			let to = T::Lookup::lookup(dest)?;
+			Self::ensure_registered(currency_id)?;
+			let amount = Self::reducible_balance(currency_id, &from, keep_alive);
+			if amount.is_zero() || from == to {
+				return Ok(().into());
+			}
 			<Self as Stp258Currency<T::AccountId>>::transfer(currency_id, &from, &to, amount)?;
 			Ok(().into())
 		}
 
-		/// Transfer some native currency to another account.
-		///
-		/// The dispatch origin for this call must be `Signed` by the
-		/// transactor.
-		#[pallet::weight(T::WeightInfo::transfer_native_currency())]
-		pub fn transfer_native_currency(
-			origin: OriginFor<T>,
-			dest: <T::Lookup as StaticLookup>::Source,
-			#[pallet::compact] amount: BalanceOf<T>,
-		) -> DispatchResultWithPostInfo {
-			let from = ensure_signed(origin)?;
-			let to = T::Lookup::lookup(dest)?;
-			T::Stp258Native::transfer(&from, &to, amount)?;
+		/// Make several transfers, possibly in different currencies, all or
+		/// none of which succeed.
+		///
+		/// The dispatch origin for this call must be `Signed` by the
+		/// transactor.
+		///
+		/// Each transfer is charged as a non-native transfer.
+		#[pallet::weight(T::WeightInfo::transfer_batch(transfers.len() as u32))]
+		pub fn transfer_batch(
+			origin: OriginFor<T>,
+			transfers: Vec<(<T::Lookup as StaticLookup>::Source, CurrencyIdOf<T>, BalanceOf<T>)>,
+		) -> DispatchResultWithPostInfo {
+			let from = ensure_signed(origin)?;
+			ensure!(
+				transfers.len() <= T::MaxBatchTransfers::get() as usize,
+				Error::<T>::TooManyBatchTransfers
+			);
+			with_transaction_result(|| {
+				for (dest, currency_id, amount) in transfers {
+					let to = T::Lookup::lookup(dest)?;
+					Self::ensure_registered(currency_id)?;
+					<Self as Stp258Currency<T::AccountId>>::transfer(currency_id, &from, &to, amount)?;
+				}
+				Ok(())
+			})?;
+			Ok(().into())
+		}
+
+		/// update amount of account `who` under `currency_id`.
+		///
+		/// The dispatch origin of this call must be `UpdateOrigin`.
+		///
+		/// Updates of the native currency are charged the heavier of the
+		/// creating and killing weights up front, and refunded to the
+		/// path actually taken.
+		#[pallet::weight(if *currency_id == T::GetStp258NativeId::get() {
+			T::WeightInfo::update_balance_native_currency_creating()
+				.max(T::WeightInfo::update_balance_native_currency_killing())
+		} else {
+			T::WeightInfo::update_balance_non_native_currency()
+		})]
+		pub fn update_balance(
+			origin: OriginFor<T>,
+			who: <T::Lookup as StaticLookup>::Source,
+			currency_id: CurrencyIdOf<T>,
+			amount: AmountOf<T>,
+		) -> DispatchResultWithPostInfo {
+			T::UpdateOrigin::ensure_origin(origin)?;
+			let dest = T::Lookup::lookup(who)?;
+			let metadata = Self::currency_metadata(currency_id).ok_or(Error::<T>::CurrencyNotRegistered)?;
+			ensure!(
+				!amount.is_positive() || metadata.status == CurrencyStatus::Active,
+				Error::<T>::CurrencyDeprecated
+			);
+			<Self as Stp258CurrencyExtended<T::AccountId>>::update_balance(currency_id, &dest, amount)?;
+
+			if currency_id == T::GetStp258NativeId::get() {
+				let actual_weight = if amount.is_positive() {
+					T::WeightInfo::update_balance_native_currency_creating()
+				} else {
+					T::WeightInfo::update_balance_native_currency_killing()
+				};
+				Ok(Some(actual_weight).into())
+			} else {
+				Ok(().into())
+			}
+		}
+
+		/// Transfer `schedule`'s total amount of `currency_id` to `dest` and
+		/// lock it there under the vesting lock.
+		///
+		/// The dispatch origin for this call must be `Signed` by the
+		/// transactor.
+		#[pallet::weight(T::WeightInfo::vested_transfer())]
+		pub fn vested_transfer(
+			origin: OriginFor<T>,
+			dest: <T::Lookup as StaticLookup>::Source,
+			currency_id: CurrencyIdOf<T>,
+			schedule: VestingScheduleOf<T>,
+		) -> DispatchResultWithPostInfo {
+			let from = ensure_signed(origin)?;
+			let to = T::Lookup::lookup(dest)?;
+			Self::ensure_registered(currency_id)?;
+			Self::do_vested_transfer(&from, &to, currency_id, schedule.clone())?;
+
+			Self::deposit_event(Event::VestingScheduleAdded(currency_id, from, to, schedule));
+			Ok(().into())
+		}
+
+		/// Release the vested balance of `currency_id` of the caller.
+		///
+		/// The dispatch origin for this call must be `Signed` by the
+		/// transactor.
+		#[pallet::weight(T::WeightInfo::claim(T::MaxVestingSchedules::get()))]
+		pub fn claim(origin: OriginFor<T>, currency_id: CurrencyIdOf<T>) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+			let locked_amount = Self::do_claim(&who, currency_id)?;
+
+			Self::deposit_event(Event::Claimed(currency_id, who, locked_amount));
+			Ok(().into())
+		}
+
+		/// Replace the vesting schedules of `who` under `currency_id`.
+		///
+		/// The dispatch origin of this call must be _Root_.
+		#[pallet::weight(T::WeightInfo::update_vesting_schedules(vesting_schedules.len() as u32))]
+		pub fn update_vesting_schedules(
+			origin: OriginFor<T>,
+			who: <T::Lookup as StaticLookup>::Source,
+			currency_id: CurrencyIdOf<T>,
+			vesting_schedules: Vec<VestingScheduleOf<T>>,
+		) -> DispatchResultWithPostInfo {
+			ensure_root(origin)?;
+			let account = T::Lookup::lookup(who)?;
+			Self::do_update_vesting_schedules(&account, currency_id, vesting_schedules)?;
+
+			Self::deposit_event(Event::VestingSchedulesUpdated(currency_id, account));
+			Ok(().into())
+		}
+
+		/// Register `currency_id` with its `metadata`.
+		///
+		/// The dispatch origin of this call must be `RegistryOrigin`.
+		#[pallet::weight(T::WeightInfo::register_currency())]
+		pub fn register_currency(
+			origin: OriginFor<T>,
+			currency_id: CurrencyIdOf<T>,
+			metadata: CurrencyMetadata,
+		) -> DispatchResultWithPostInfo {
+			T::RegistryOrigin::ensure_origin(origin)?;
+			ensure!(
+				!CurrencyRegistry::<T>::contains_key(currency_id),
+				Error::<T>::CurrencyAlreadyRegistered
+			);
+			let metadata = Self::do_set_currency_metadata(currency_id, metadata);
+
+			Self::deposit_event(Event::CurrencyRegistered(currency_id, metadata));
+			Ok(().into())
+		}
+
+		/// Replace the metadata of the registered `currency_id`, e.g. to
+		/// deprecate it.
+		///
+		/// The dispatch origin of this call must be `RegistryOrigin`.
+		#[pallet::weight(T::WeightInfo::update_currency())]
+		pub fn update_currency(
+			origin: OriginFor<T>,
+			currency_id: CurrencyIdOf<T>,
+			metadata: CurrencyMetadata,
+		) -> DispatchResultWithPostInfo {
+			T::RegistryOrigin::ensure_origin(origin)?;
+			Self::ensure_registered(currency_id)?;
+			let metadata = Self::do_set_currency_metadata(currency_id, metadata);
+
+			Self::deposit_event(Event::CurrencyUpdated(currency_id, metadata));
+			Ok(().into())
+		}
+
+		/// Halt every transfer, deposit, withdrawal and balance update of
+		/// `currency_id`.
+		///
+		/// The dispatch origin of this call must be `PauseOrigin`.
+		#[pallet::weight(T::WeightInfo::pause_currency())]
+		pub fn pause_currency(origin: OriginFor<T>, currency_id: CurrencyIdOf<T>) -> DispatchResultWithPostInfo {
+			T::PauseOrigin::ensure_origin(origin)?;
+			PausedCurrencies::<T>::insert(currency_id, true);
+
+			Self::deposit_event(Event::CurrencyPaused(currency_id));
+			Ok(().into())
+		}
+
+		/// Resume movements of `currency_id`.
+		///
+		/// The dispatch origin of this call must be `PauseOrigin`.
+		#[pallet::weight(T::WeightInfo::unpause_currency())]
+		pub fn unpause_currency(origin: OriginFor<T>, currency_id: CurrencyIdOf<T>) -> DispatchResultWithPostInfo {
+			T::PauseOrigin::ensure_origin(origin)?;
+			PausedCurrencies::<T>::remove(currency_id);
+
+			Self::deposit_event(Event::CurrencyUnpaused(currency_id));
+			Ok(().into())
+		}
+
+		/// Stop `currency_id` from leaving the account `who`.
+		///
+		/// The dispatch origin of this call must be `FreezeOrigin` or
+		/// `Signed` by the freezer of `currency_id`.
+		#[pallet::weight(T::WeightInfo::freeze_account())]
+		pub fn freeze_account(
+			origin: OriginFor<T>,
+			who: <T::Lookup as StaticLookup>::Source,
+			currency_id: CurrencyIdOf<T>,
+		) -> DispatchResultWithPostInfo {
+			Self::ensure_freezer(origin, currency_id)?;
+			let account = T::Lookup::lookup(who)?;
+			FrozenAccounts::<T>::insert(&account, currency_id, true);
+
+			Self::deposit_event(Event::AccountFrozen(currency_id, account));
+			Ok(().into())
+		}
+
+		/// Let `currency_id` leave the account `who` again.
+		///
+		/// The dispatch origin of this call must be `FreezeOrigin` or
+		/// `Signed` by the freezer of `currency_id`.
+		#[pallet::weight(T::WeightInfo::thaw_account())]
+		pub fn thaw_account(
+			origin: OriginFor<T>,
+			who: <T::Lookup as StaticLookup>::Source,
+			currency_id: CurrencyIdOf<T>,
+		) -> DispatchResultWithPostInfo {
+			Self::ensure_freezer(origin, currency_id)?;
+			let account = T::Lookup::lookup(who)?;
+			FrozenAccounts::<T>::remove(&account, currency_id);
+
+			Self::deposit_event(Event::AccountThawed(currency_id, account));
+			Ok(().into())
+		}
+
+		/// Appoint the issuer, freezer and admin of `currency_id`.
+		///
+		/// The dispatch origin of this call must be `RegistryOrigin`.
+		#[pallet::weight(T::WeightInfo::set_roles())]
+		pub fn set_roles(
+			origin: OriginFor<T>,
+			currency_id: CurrencyIdOf<T>,
+			issuer: <T::Lookup as StaticLookup>::Source,
+			freezer: <T::Lookup as StaticLookup>::Source,
+			admin: <T::Lookup as StaticLookup>::Source,
+		) -> DispatchResultWithPostInfo {
+			T::RegistryOrigin::ensure_origin(origin)?;
+			Self::ensure_registered(currency_id)?;
+			Self::do_set_roles(currency_id, issuer, freezer, admin)?;
+			Ok(().into())
+		}
+
+		/// Hand the roles of `currency_id` over to other accounts.
+		///
+		/// The dispatch origin for this call must be `Signed` by the admin
+		/// of `currency_id`.
+		#[pallet::weight(T::WeightInfo::transfer_roles())]
+		pub fn transfer_roles(
+			origin: OriginFor<T>,
+			currency_id: CurrencyIdOf<T>,
+			issuer: <T::Lookup as StaticLookup>::Source,
+			freezer: <T::Lookup as StaticLookup>::Source,
+			admin: <T::Lookup as StaticLookup>::Source,
+		) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+			let roles = Self::roles(currency_id).ok_or(Error::<T>::RolesNotSet)?;
+			ensure!(roles.admin == who, Error::<T>::NoPermission);
+			Self::do_set_roles(currency_id, issuer, freezer, admin)?;
+			Ok(().into())
+		}
+
+		/// Issue `amount` of `currency_id` to `dest`.
+		///
+		/// The dispatch origin for this call must be `Signed` by the issuer
+		/// of `currency_id`.
+		#[pallet::weight(T::WeightInfo::mint())]
+		pub fn mint(
+			origin: OriginFor<T>,
+			currency_id: CurrencyIdOf<T>,
+			dest: <T::Lookup as StaticLookup>::Source,
+			#[pallet::compact] amount: BalanceOf<T>,
+		) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+			Self::ensure_issuer(currency_id, &who)?;
+			let dest = T::Lookup::lookup(dest)?;
+			let metadata = Self::currency_metadata(currency_id).ok_or(Error::<T>::CurrencyNotRegistered)?;
+			ensure!(metadata.status == CurrencyStatus::Active, Error::<T>::CurrencyDeprecated);
+			<Self as Stp258Currency<T::AccountId>>::deposit(currency_id, &dest, amount)?;
+			Ok(().into())
+		}
+
+		/// Destroy `amount` of `currency_id` held by `who`.
+		///
+		/// The dispatch origin for this call must be `Signed` by the issuer
+		/// of `currency_id`.
+		#[pallet::weight(T::WeightInfo::burn())]
+		pub fn burn(
+			origin: OriginFor<T>,
+			currency_id: CurrencyIdOf<T>,
+			who: <T::Lookup as StaticLookup>::Source,
+			#[pallet::compact] amount: BalanceOf<T>,
+		) -> DispatchResultWithPostInfo {
+			let issuer = ensure_signed(origin)?;
+			Self::ensure_issuer(currency_id, &issuer)?;
+			let who = T::Lookup::lookup(who)?;
+			<Self as Stp258Currency<T::AccountId>>::withdraw(currency_id, &who, amount)?;
+			Ok(().into())
+		}
+
+		/// Cap the total issuance of `currency_id` at `max_supply`, or lift
+		/// the cap with `None`.
+		///
+		/// The dispatch origin of this call must be `RegistryOrigin`.
+		#[pallet::weight(T::WeightInfo::set_max_supply())]
+		pub fn set_max_supply(
+			origin: OriginFor<T>,
+			currency_id: CurrencyIdOf<T>,
+			max_supply: Option<BalanceOf<T>>,
+		) -> DispatchResultWithPostInfo {
+			T::RegistryOrigin::ensure_origin(origin)?;
+			Self::ensure_registered(currency_id)?;
+			MaxSupply::<T>::set(currency_id, max_supply);
+
+			Self::deposit_event(Event::MaxSupplySet(currency_id, max_supply));
+			Ok(().into())
+		}
+
+		/// Limit how much of `currency_id` can be minted and burned per
+		/// issuance window, or lift the limits with `None`.
+		///
+		/// The dispatch origin of this call must be `RegistryOrigin`.
+		#[pallet::weight(T::WeightInfo::set_issuance_limits())]
+		pub fn set_issuance_limits(
+			origin: OriginFor<T>,
+			currency_id: CurrencyIdOf<T>,
+			limits: Option<IssuanceLimits<BalanceOf<T>>>,
+		) -> DispatchResultWithPostInfo {
+			T::RegistryOrigin::ensure_origin(origin)?;
+			Self::ensure_registered(currency_id)?;
+			IssuanceLimitsOf::<T>::set(currency_id, limits.clone());
+
+			Self::deposit_event(Event::IssuanceLimitsSet(currency_id, limits));
+			Ok(().into())
+		}
+
+		/// Allow `spender` to transfer up to `amount` of `currency_id` out
+		/// of the caller's balance, replacing any previous allowance.
+		///
+		/// The dispatch origin for this call must be `Signed` by the owner.
+		#[pallet::weight(T::WeightInfo::approve())]
+		pub fn approve(
+			origin: OriginFor<T>,
+			spender: <T::Lookup as StaticLookup>::Source,
+			currency_id: CurrencyIdOf<T>,
+			#[pallet::compact] amount: BalanceOf<T>,
+		) -> DispatchResultWithPostInfo {
+			let owner = ensure_signed(origin)?;
+			let spender = T::Lookup::lookup(spender)?;
+			Self::ensure_registered(currency_id)?;
+			Self::do_set_allowance(currency_id, owner, spender, amount);
+			Ok(().into())
+		}
+
+		/// Raise the allowance of `spender` over the caller's `currency_id`
+		/// by `amount`.
+		///
+		/// The dispatch origin for this call must be `Signed` by the owner.
+		#[pallet::weight(T::WeightInfo::increase_allowance())]
+		pub fn increase_allowance(
+			origin: OriginFor<T>,
+			spender: <T::Lookup as StaticLookup>::Source,
+			currency_id: CurrencyIdOf<T>,
+			#[pallet::compact] amount: BalanceOf<T>,
+		) -> DispatchResultWithPostInfo {
+			let owner = ensure_signed(origin)?;
+			let spender = T::Lookup::lookup(spender)?;
+			Self::ensure_registered(currency_id)?;
+			let allowance = Self::allowance((currency_id, &owner), &spender)
+				.checked_add(&amount)
+				.ok_or(Error::<T>::NumOverflow)?;
+			Self::do_set_allowance(currency_id, owner, spender, allowance);
+			Ok(().into())
+		}
+
+		/// Lower the allowance of `spender` over the caller's `currency_id`
+		/// by `amount`.
+		///
+		/// The dispatch origin for this call must be `Signed` by the owner.
+		#[pallet::weight(T::WeightInfo::decrease_allowance())]
+		pub fn decrease_allowance(
+			origin: OriginFor<T>,
+			spender: <T::Lookup as StaticLookup>::Source,
+			currency_id: CurrencyIdOf<T>,
+			#[pallet::compact] amount: BalanceOf<T>,
+		) -> DispatchResultWithPostInfo {
+			let owner = ensure_signed(origin)?;
+			let spender = T::Lookup::lookup(spender)?;
+			let allowance = Self::allowance((currency_id, &owner), &spender)
+				.checked_sub(&amount)
+				.ok_or(Error::<T>::InsufficientAllowance)?;
+			Self::do_set_allowance(currency_id, owner, spender, allowance);
+			Ok(().into())
+		}
+
+		/// Transfer `amount` of `currency_id` from `owner` to `dest`,
+		/// consuming the caller's allowance.
+		///
+		/// The dispatch origin for this call must be `Signed` by the
+		/// spender.
+		#[pallet::weight(T::WeightInfo::transfer_from())]
+		pub fn transfer_from(
+			origin: OriginFor<T>,
+			owner: <T::Lookup as StaticLookup>::Source,
+			dest: <T::Lookup as StaticLookup>::Source,
+			currency_id: CurrencyIdOf<T>,
+			#[pallet::compact] amount: BalanceOf<T>,
+		) -> DispatchResultWithPostInfo {
+			let spender = ensure_signed(origin)?;
+			let owner = T::Lookup::lookup(owner)?;
+			let to = T::Lookup::lookup(dest)?;
+			Self::ensure_registered(currency_id)?;
+			let allowance = Self::allowance((currency_id, &owner), &spender)
+				.checked_sub(&amount)
+				.ok_or(Error::<T>::InsufficientAllowance)?;
+			<Self as Stp258Currency<T::AccountId>>::transfer(currency_id, &owner, &to, amount)?;
+			Self::do_set_allowance(currency_id, owner, spender, allowance);
+			Ok(().into())
+		}
+
+		/// Pay the caller's transaction fees in `currency_id`, or in the
+		/// native currency with `None`.
+		///
+		/// The dispatch origin for this call must be `Signed` by the payer.
+		#[pallet::weight(T::WeightInfo::set_fee_currency())]
+		pub fn set_fee_currency(
+			origin: OriginFor<T>,
+			currency_id: Option<CurrencyIdOf<T>>,
+		) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+			if let Some(currency_id) = currency_id {
+				Self::ensure_registered(currency_id)?;
+			}
+			FeeCurrencies::<T>::set(&who, currency_id);
+
+			Self::deposit_event(Event::FeeCurrencySet(who, currency_id));
+			Ok(().into())
+		}
+
+		/// Pay `amount` of `from_currency_id` and have `dest` receive its
+		/// worth in `to_currency_id`, swapped through `LiquidityAccount`.
+		/// Fails if that is less than `min_received`.
+		///
+		/// The dispatch origin for this call must be `Signed` by the
+		/// transactor.
+		#[pallet::weight(T::WeightInfo::transfer_converted())]
+		pub fn transfer_converted(
+			origin: OriginFor<T>,
+			dest: <T::Lookup as StaticLookup>::Source,
+			from_currency_id: CurrencyIdOf<T>,
+			to_currency_id: CurrencyIdOf<T>,
+			#[pallet::compact] amount: BalanceOf<T>,
+			#[pallet::compact] min_received: BalanceOf<T>,
+		) -> DispatchResultWithPostInfo {
+			let from = ensure_signed(origin)?;
+			let to = T::Lookup::lookup(dest)?;
+			Self::ensure_registered(from_currency_id)?;
+			Self::ensure_registered(to_currency_id)?;
+			let received = Self::convert(from_currency_id, to_currency_id, amount)?;
+			ensure!(received >= min_received, Error::<T>::ReceivedTooLittle);
+			let liquidity = T::LiquidityAccount::get();
+			with_transaction_result(|| {
+				<Self as Stp258Currency<T::AccountId>>::transfer(from_currency_id, &from, &liquidity, amount)?;
+				<Self as Stp258Currency<T::AccountId>>::transfer(to_currency_id, &liquidity, &to, received)?;
+				Ok(())
+			})?;
+
+			Self::deposit_event(Event::TransferredConverted(
+				from_currency_id,
+				to_currency_id,
+				from,
+				to,
+				amount,
+				received,
+			));
+			Ok(().into())
+		}
+	}
+}
+
+impl<T: Config> Pallet<T> {
+	/// Set the lock `lock_id` on `currency_id` of `who`, removing it
+	/// automatically at block `until`. Any previous expiry of the lock is
+	/// replaced.
+	pub fn set_lock_until(
+		lock_id: LockIdentifier,
+		currency_id: CurrencyIdOf<T>,
+		who: &T::AccountId,
+		amount: BalanceOf<T>,
+		until: T::BlockNumber,
+	) -> DispatchResult {
+		ensure!(
+			until > frame_system::Module::<T>::block_number(),
+			Error::<T>::LockExpiryInPast
+		);
+		with_transaction_result(|| {
+			// setting the lock clears its previous expiry, freeing that slot
+			<Self as Stp258CurrencyLockable<T::AccountId>>::set_lock(lock_id, currency_id, who, amount)?;
+			ensure!(
+				(ExpiringLocks::<T>::decode_len(until).unwrap_or(0) as u32) < T::MaxExpiringLocksPerBlock::get(),
+				Error::<T>::TooManyExpiringLocks
+			);
+			ExpiringLocks::<T>::append(until, (who.clone(), currency_id, lock_id));
+			LockExpiries::<T>::insert((who, currency_id), lock_id, until);
+			Ok(())
+		})
+	}
+
+	/// Forget the expiry of the lock `lock_id` on `currency_id` of `who`,
+	/// if any, along with its entry in `ExpiringLocks`.
+	fn clear_lock_expiry(lock_id: LockIdentifier, currency_id: CurrencyIdOf<T>, who: &T::AccountId) {
+		if let Some(until) = LockExpiries::<T>::take((who, currency_id), lock_id) {
+			ExpiringLocks::<T>::mutate_exists(until, |maybe_expiring| {
+				if let Some(expiring) = maybe_expiring.as_mut() {
+					expiring.retain(|(account, id, lock)| !(account == who && *id == currency_id && *lock == lock_id));
+				}
+				if maybe_expiring.as_ref().map_or(false, Vec::is_empty) {
+					*maybe_expiring = None;
+				}
+			});
+		}
+	}
+
+	/// Returns the worth of `amount` of `from_currency_id` in
+	/// `to_currency_id`, using the base unit of each currency to account
+	/// for their decimals.
+	pub fn convert(
+		from_currency_id: CurrencyIdOf<T>,
+		to_currency_id: CurrencyIdOf<T>,
+		amount: BalanceOf<T>,
+	) -> Result<BalanceOf<T>, Error<T>> {
+		Self::do_convert(from_currency_id, to_currency_id, amount, Self::convert_amount)
+	}
+
+	/// Like `convert`, but rounds up instead of down, so that no non-zero
+	/// amount is worth nothing.
+	pub(crate) fn convert_up(
+		from_currency_id: CurrencyIdOf<T>,
+		to_currency_id: CurrencyIdOf<T>,
+		amount: BalanceOf<T>,
+	) -> Result<BalanceOf<T>, Error<T>> {
+		Self::do_convert(from_currency_id, to_currency_id, amount, Self::convert_amount_up)
+	}
+
+	fn do_convert(
+		from_currency_id: CurrencyIdOf<T>,
+		to_currency_id: CurrencyIdOf<T>,
+		amount: BalanceOf<T>,
+		convert_amount: fn(Price, u128, u128, u128) -> Option<u128>,
+	) -> Result<BalanceOf<T>, Error<T>> {
+		if from_currency_id == to_currency_id {
+			return Ok(amount);
+		}
+		let price =
+			T::PriceProvider::get_price(from_currency_id, to_currency_id).ok_or(Error::<T>::PriceUnavailable)?;
+		let base_unit = |currency_id| -> u128 {
+			<Self as Stp258Currency<T::AccountId>>::base_unit(currency_id).unique_saturated_into()
+		};
+		let converted = convert_amount(
+			price,
+			amount.unique_saturated_into(),
+			base_unit(from_currency_id),
+			base_unit(to_currency_id),
+		)
+		.ok_or(Error::<T>::ConversionOverflow)?;
+		TryFrom::try_from(converted).map_err(|_| Error::<T>::ConversionOverflow)
+	}
+
+	/// Returns `amount` in units of `from_unit` converted at `price` into
+	/// units of `to_unit`.
+	///
+	/// The unit ratio is folded into the price first, so that `amount` is
+	/// never scaled up by a base unit on its own.
+	fn convert_amount(price: Price, amount: u128, from_unit: u128, to_unit: u128) -> Option<u128> {
+		price
+			.checked_mul(&Price::checked_from_rational(to_unit, from_unit)?)?
+			.checked_mul_int(amount)
+	}
+
+	/// Like `convert_amount`, but rounds up.
+	fn convert_amount_up(price: Price, amount: u128, from_unit: u128, to_unit: u128) -> Option<u128> {
+		let rate = price
+			.checked_mul(&Price::checked_from_rational(to_unit, from_unit)?)?
+			.into_inner();
+		let accuracy = Price::accuracy();
+		let converted = multiply_by_rational(amount, rate, accuracy).ok()?;
+		// `amount * rate` is a multiple of `accuracy` iff the product of
+		// their remainders is
+		if (amount % accuracy) * (rate % accuracy) % accuracy == 0 {
+			Some(converted)
+		} else {
+			converted.checked_add(1)
+		}
+	}
+
+	/// Returns the locks set through this module on `currency_id` of
+	/// `who`.
+	pub fn locks(currency_id: CurrencyIdOf<T>, who: &T::AccountId) -> Vec<(LockIdentifier, BalanceOf<T>)> {
+		Locks::<T>::iter_prefix((who, currency_id)).collect()
+	}
+
+	/// Returns the free balance of `who` in `currency_id` that no lock,
+	/// whether set through this module or on the backend, keeps from being
+	/// transferred.
+	pub fn transferable_balance(currency_id: CurrencyIdOf<T>, who: &T::AccountId) -> BalanceOf<T> {
+		Self::reducible_balance(currency_id, who, false)
+	}
+
+	/// Returns how much of `currency_id` `who` can transfer away, as
+	/// allowed by the locks of both the backends and this module.
+	///
+	/// If `keep_alive` is set, the minimum balance is kept back as well.
+	pub fn reducible_balance(currency_id: CurrencyIdOf<T>, who: &T::AccountId, keep_alive: bool) -> BalanceOf<T> {
+		let free_balance = <Self as Stp258Currency<T::AccountId>>::free_balance(currency_id, who);
+		let max = if keep_alive {
+			<Self as Stp258Currency<T::AccountId>>::total_balance(currency_id, who)
+				.saturating_sub(<Self as Stp258Currency<T::AccountId>>::minimum_balance(currency_id))
+				.min(free_balance)
+		} else {
+			free_balance
+		};
+		let can_transfer = |amount| {
+			<Self as Stp258CurrencyReasonLockable<T::AccountId>>::ensure_can_withdraw_for(
+				currency_id,
+				who,
+				amount,
+				WithdrawReasons::TRANSFER,
+			)
+			.is_ok()
+		};
+		if max.is_zero() || can_transfer(max) {
+			return max;
+		}
+
+		// The backends do not expose their frozen balance, so search for the
+		// largest amount they let go of, starting from the locks known here.
+		let known_frozen = Locks::<T>::iter_prefix_values((who, currency_id))
+			.fold(Zero::zero(), |frozen: BalanceOf<T>, locked| frozen.max(locked));
+		let mut low = free_balance.saturating_sub(known_frozen).min(max);
+		if !can_transfer(low) {
+			low = Zero::zero();
+		}
+		let mut high = max;
+		let two: BalanceOf<T> = 2u32.into();
+		while high.saturating_sub(low) > One::one() {
+			let mid = low + (high - low) / two;
+			if can_transfer(mid) {
+				low = mid;
+			} else {
+				high = mid;
+			}
+		}
+		low
+	}
+
+	/// Returns the total balance of `who` in every registered currency it
+	/// holds.
+	pub fn all_balances(who: &T::AccountId) -> Vec<(CurrencyIdOf<T>, BalanceOf<T>)> {
+		CurrencyRegistry::<T>::iter()
+			.map(|(currency_id, _)| {
+				(
+					currency_id,
+					<Self as Stp258Currency<T::AccountId>>::total_balance(currency_id, who),
+				)
+			})
+			.filter(|(_, balance)| !balance.is_zero())
+			.collect()
+	}
+
+	/// Returns how much more of `currency_id` can be issued, or `None` if
+	/// its supply is uncapped.
+	pub fn supply_headroom(currency_id: CurrencyIdOf<T>) -> Option<BalanceOf<T>> {
+		Self::max_supply(currency_id).map(|max_supply| {
+			max_supply.saturating_sub(<Self as Stp258Currency<T::AccountId>>::total_issuance(currency_id))
+		})
+	}
+
+	/// Ensure issuing `amount` of `currency_id` stays within its maximum
+	/// supply.
+	fn ensure_within_supply_cap(currency_id: CurrencyIdOf<T>, amount: BalanceOf<T>) -> DispatchResult {
+		if let Some(headroom) = Self::supply_headroom(currency_id) {
+			ensure!(amount <= headroom, Error::<T>::SupplyCapExceeded);
+		}
+		Ok(())
+	}
+
+	/// Whether issuance noted at `block` still counts at `now`.
+	fn in_issuance_window(block: T::BlockNumber, now: T::BlockNumber) -> bool {
+		let window = T::IssuanceWindow::get();
+		window.is_zero() || block.saturating_add(window) > now
+	}
+
+	/// The amounts of `currency_id` minted and burned in the last
+	/// `IssuanceWindow` blocks, this one included.
+	pub fn issued_in_window(currency_id: CurrencyIdOf<T>) -> (BalanceOf<T>, BalanceOf<T>) {
+		let now = frame_system::Module::<T>::block_number();
+		Self::window_issuance(currency_id)
+			.into_iter()
+			.filter(|(block, _, _)| Self::in_issuance_window(*block, now))
+			.fold((Zero::zero(), Zero::zero()), |(minted, burned): (BalanceOf<T>, BalanceOf<T>), (_, m, b)| {
+				(minted.saturating_add(m), burned.saturating_add(b))
+			})
+	}
+
+	/// Ensure minting `minted` and burning `burned` of `currency_id` stays
+	/// within the limits of the last issuance window.
+	fn ensure_within_issuance_limits(
+		currency_id: CurrencyIdOf<T>,
+		minted: BalanceOf<T>,
+		burned: BalanceOf<T>,
+	) -> DispatchResult {
+		if let Some(limits) = Self::issuance_limits(currency_id) {
+			let (window_minted, window_burned) = Self::issued_in_window(currency_id);
+			ensure!(
+				window_minted.saturating_add(minted) <= limits.mint,
+				Error::<T>::MintLimitExceeded
+			);
+			ensure!(
+				window_burned.saturating_add(burned) <= limits.burn,
+				Error::<T>::BurnLimitExceeded
+			);
+		}
+		Ok(())
+	}
+
+	/// Count `minted` and `burned` of `currency_id` against the issuance
+	/// window, if the currency is limited, and forget what fell out of it.
+	fn note_issuance(currency_id: CurrencyIdOf<T>, minted: BalanceOf<T>, burned: BalanceOf<T>) {
+		if IssuanceLimitsOf::<T>::contains_key(currency_id) {
+			let now = frame_system::Module::<T>::block_number();
+			WindowIssuance::<T>::mutate(currency_id, |issuance| {
+				issuance.retain(|(block, _, _)| Self::in_issuance_window(*block, now));
+				match issuance.last_mut() {
+					// a zero window never forgets, so one entry is enough
+					Some((block, window_minted, window_burned))
+						if *block == now || T::IssuanceWindow::get().is_zero() =>
+					{
+						*window_minted = window_minted.saturating_add(minted);
+						*window_burned = window_burned.saturating_add(burned);
+					}
+					_ => issuance.push((now, minted, burned)),
+				}
+			});
+		}
+	}
+
+	/// Set the allowance of `spender` over the `currency_id` of `owner`.
+	fn do_set_allowance(
+		currency_id: CurrencyIdOf<T>,
+		owner: T::AccountId,
+		spender: T::AccountId,
+		allowance: BalanceOf<T>,
+	) {
+		if allowance.is_zero() {
+			Allowances::<T>::remove((currency_id, &owner), &spender);
+		} else {
+			Allowances::<T>::insert((currency_id, &owner), &spender, allowance);
+		}
+
+		Self::deposit_event(Event::Approval(currency_id, owner, spender, allowance));
+	}
+
+	/// Ensure `currency_id` is in the currency registry.
+	fn ensure_registered(currency_id: CurrencyIdOf<T>) -> DispatchResult {
+		ensure!(
+			CurrencyRegistry::<T>::contains_key(currency_id),
+			Error::<T>::CurrencyNotRegistered
+		);
+		Ok(())
+	}
+
+	/// Ensure movements of `currency_id` are not paused.
+	fn ensure_not_paused(currency_id: CurrencyIdOf<T>) -> DispatchResult {
+		ensure!(!Self::is_paused(currency_id), Error::<T>::CurrencyPaused);
+		Ok(())
+	}
+
+	/// Ensure `origin` is `FreezeOrigin` or the freezer of `currency_id`.
+	fn ensure_freezer(origin: OriginFor<T>, currency_id: CurrencyIdOf<T>) -> DispatchResult {
+		let origin = match T::FreezeOrigin::try_origin(origin) {
+			Ok(_) => return Ok(()),
+			Err(origin) => origin,
+		};
+		let who = ensure_signed(origin)?;
+		ensure!(
+			Self::roles(currency_id).map_or(false, |roles| roles.freezer == who),
+			Error::<T>::NoPermission
+		);
+		Ok(())
+	}
+
+	/// Ensure `who` is the issuer of `currency_id`.
+	fn ensure_issuer(currency_id: CurrencyIdOf<T>, who: &T::AccountId) -> DispatchResult {
+		let roles = Self::roles(currency_id).ok_or(Error::<T>::RolesNotSet)?;
+		ensure!(&roles.issuer == who, Error::<T>::NoPermission);
+		Ok(())
+	}
+
+	fn do_set_roles(
+		currency_id: CurrencyIdOf<T>,
+		issuer: <T::Lookup as StaticLookup>::Source,
+		freezer: <T::Lookup as StaticLookup>::Source,
+		admin: <T::Lookup as StaticLookup>::Source,
+	) -> DispatchResult {
+		let issuer = T::Lookup::lookup(issuer)?;
+		let freezer = T::Lookup::lookup(freezer)?;
+		let admin = T::Lookup::lookup(admin)?;
+		Roles::<T>::insert(
+			currency_id,
+			CurrencyRoles {
+				issuer: issuer.clone(),
+				freezer: freezer.clone(),
+				admin: admin.clone(),
+			},
+		);
+
+		Self::deposit_event(Event::RolesSet(currency_id, issuer, freezer, admin));
+		Ok(())
+	}
+
+	/// Ensure `currency_id` may leave the account `who`.
+	fn ensure_not_frozen(currency_id: CurrencyIdOf<T>, who: &T::AccountId) -> DispatchResult {
+		ensure!(!Self::is_frozen(who, currency_id), Error::<T>::AccountFrozen);
+		Ok(())
+	}
+
+	/// Store `metadata` for `currency_id`, deriving its native flag.
+	fn do_set_currency_metadata(currency_id: CurrencyIdOf<T>, mut metadata: CurrencyMetadata) -> CurrencyMetadata {
+		metadata.is_native = currency_id == T::GetStp258NativeId::get();
+		CurrencyRegistry::<T>::insert(currency_id, metadata.clone());
+		metadata
+	}
+
+	/// Returns the vesting balance of `currency_id` still locked for `who`,
+	/// dropping the schedules that have fully vested.
+	fn locked_balance(who: &T::AccountId, currency_id: CurrencyIdOf<T>) -> BalanceOf<T> {
+		let now = frame_system::Module::<T>::block_number();
+		VestingSchedules::<T>::mutate_exists(who, currency_id, |maybe_schedules| {
+			let total = if let Some(schedules) = maybe_schedules.as_mut() {
+				let mut total: BalanceOf<T> = Zero::zero();
+				schedules.retain(|s| {
+					let amount = s.locked_amount(now);
+					total = total.saturating_add(amount);
+					!amount.is_zero()
+				});
+				total
+			} else {
+				Zero::zero()
+			};
+			if total.is_zero() {
+				*maybe_schedules = None;
+			}
+			total
+		})
+	}
+
+	/// Returns the total amount of `schedule` if it is valid.
+	fn ensure_valid_vesting_schedule(schedule: &VestingScheduleOf<T>) -> result::Result<BalanceOf<T>, DispatchError> {
+		ensure!(!schedule.period.is_zero(), Error::<T>::ZeroVestingPeriod);
+		ensure!(!schedule.period_count.is_zero(), Error::<T>::ZeroVestingPeriodCount);
+		ensure!(schedule.end().is_some(), Error::<T>::NumOverflow);
+
+		schedule.total_amount().ok_or_else(|| Error::<T>::NumOverflow.into())
+	}
+
+	fn do_claim(who: &T::AccountId, currency_id: CurrencyIdOf<T>) -> result::Result<BalanceOf<T>, DispatchError> {
+		let locked = Self::locked_balance(who, currency_id);
+		if locked.is_zero() {
+			<Self as Stp258CurrencyLockable<T::AccountId>>::remove_lock(VESTING_LOCK_ID, currency_id, who)?;
+		} else {
+			<Self as Stp258CurrencyLockable<T::AccountId>>::set_lock(VESTING_LOCK_ID, currency_id, who, locked)?;
+		}
+		Ok(locked)
+	}
+
+	fn do_vested_transfer(
+		from: &T::AccountId,
+		to: &T::AccountId,
+		currency_id: CurrencyIdOf<T>,
+		schedule: VestingScheduleOf<T>,
+	) -> DispatchResult {
+		let schedule_amount = Self::ensure_valid_vesting_schedule(&schedule)?;
+		ensure!(
+			(Self::vesting_schedules(to, currency_id).len() as u32) < T::MaxVestingSchedules::get(),
+			Error::<T>::TooManyVestingSchedules
+		);
+
+		let total_amount = Self::locked_balance(to, currency_id)
+			.checked_add(&schedule_amount)
+			.ok_or(Error::<T>::NumOverflow)?;
+
+		with_transaction_result(|| {
+			<Self as Stp258Currency<T::AccountId>>::transfer(currency_id, from, to, schedule_amount)?;
+			<Self as Stp258CurrencyLockable<T::AccountId>>::set_lock(VESTING_LOCK_ID, currency_id, to, total_amount)?;
+			VestingSchedules::<T>::append(to, currency_id, schedule);
+			Ok(())
+		})
+	}
+
+	fn do_update_vesting_schedules(
+		who: &T::AccountId,
+		currency_id: CurrencyIdOf<T>,
+		schedules: Vec<VestingScheduleOf<T>>,
+	) -> DispatchResult {
+		ensure!(
+			schedules.len() as u32 <= T::MaxVestingSchedules::get(),
+			Error::<T>::TooManyVestingSchedules
+		);
+
+		let total_amount = schedules
+			.iter()
+			.try_fold::<_, _, result::Result<BalanceOf<T>, DispatchError>>(Zero::zero(), |acc_amount, schedule| {
+				let amount = Self::ensure_valid_vesting_schedule(schedule)?;
+				Ok(acc_amount.checked_add(&amount).ok_or(Error::<T>::NumOverflow)?)
+			})?;
+		ensure!(
+			<Self as Stp258Currency<T::AccountId>>::free_balance(currency_id, who) >= total_amount,
+			Error::<T>::InsufficientBalanceToLock,
+		);
+
+		if total_amount.is_zero() {
+			<Self as Stp258CurrencyLockable<T::AccountId>>::remove_lock(VESTING_LOCK_ID, currency_id, who)?;
+			VestingSchedules::<T>::remove(who, currency_id);
+		} else {
+			<Self as Stp258CurrencyLockable<T::AccountId>>::set_lock(VESTING_LOCK_ID, currency_id, who, total_amount)?;
+			VestingSchedules::<T>::insert(who, currency_id, schedules);
+		}
+		Ok(())
+	}
+
+	/// Ensure `who` keeps at least the minimum balance of `currency_id`
+	/// after `amount` has left the account.
+	fn ensure_keep_alive(currency_id: CurrencyIdOf<T>, who: &T::AccountId, amount: BalanceOf<T>) -> DispatchResult {
+		let remaining = <Self as Stp258Currency<T::AccountId>>::total_balance(currency_id, who).saturating_sub(amount);
+		ensure!(
+			remaining >= <Self as Stp258Currency<T::AccountId>>::minimum_balance(currency_id),
+			Error::<T>::KeepAlive
+		);
+		Ok(())
+	}
 
-			Self::deposit_event(Event::Transferred(T::GetStp258NativeId::get(), from, to, amount));
-			Ok(().into())
+	/// Withdraw `amount` of `currency_id` from `who` on the backend,
+	/// failing only on the locks restricting `reasons`, and without reaping
+	/// the account.
+	fn do_withdraw_for(
+		currency_id: CurrencyIdOf<T>,
+		who: &T::AccountId,
+		amount: BalanceOf<T>,
+		reasons: WithdrawReasons,
+	) -> DispatchResult {
+		if currency_id == T::GetStp258NativeId::get() {
+			T::Stp258Native::withdraw_for(who, amount, reasons)
+		} else {
+			Self::ensure_keep_alive(currency_id, who, amount)?;
+			Self::ensure_reason_locks(currency_id, who, amount, reasons)?;
+			T::Stp258Currency::withdraw(currency_id, who, amount)
 		}
+	}
 
-		/// update amount of account `who` under `currency_id`.
-		///
-		/// The dispatch origin of this call must be _Root_.
-		#[pallet::weight(T::WeightInfo::update_balance_non_native_currency())]
-		pub fn update_balance(
-			origin: OriginFor<T>,
-			who: <T::Lookup as StaticLookup>::Source,
-			currency_id: CurrencyIdOf<T>,
-			amount: AmountOf<T>,
-		) -> DispatchResultWithPostInfo {
-			ensure_root(origin)?;
-			let dest = T::Lookup::lookup(who)?;
-			<Self as Stp258CurrencyExtended<T::AccountId>>::update_balance(currency_id, &dest, amount)?;
-			Ok(().into())
+	/// Take a transaction fee of `amount` of `currency_id` from `who`.
+	///
+	/// Only locks restricting `reasons` apply. The caller is expected to
+	/// have picked a currency that is neither paused nor frozen for `who`,
+	/// and supply caps and issuance limits do not apply to fees.
+	pub(crate) fn withdraw_fee(
+		currency_id: CurrencyIdOf<T>,
+		who: &T::AccountId,
+		amount: BalanceOf<T>,
+		reasons: WithdrawReasons,
+	) -> DispatchResult {
+		if amount.is_zero() {
+			return Ok(());
+		}
+		Self::do_withdraw_for(currency_id, who, amount, reasons)
+	}
+
+	/// Pay `amount` of a transaction fee in `currency_id` out to `who`,
+	/// either as a refund or to the fee receiver, bypassing the same rules
+	/// as `withdraw_fee`.
+	pub(crate) fn deposit_fee(
+		currency_id: CurrencyIdOf<T>,
+		who: &T::AccountId,
+		amount: BalanceOf<T>,
+	) -> DispatchResult {
+		if amount.is_zero() {
+			return Ok(());
+		}
+		if currency_id == T::GetStp258NativeId::get() {
+			T::Stp258Native::deposit(who, amount)
+		} else {
+			T::Stp258Currency::deposit(currency_id, who, amount)
 		}
 	}
+
+	/// Ensure the reason locks on the non-native `currency_id` of `who`
+	/// that restrict `reasons` still hold once `amount` has been withdrawn.
+	fn ensure_reason_locks(
+		currency_id: CurrencyIdOf<T>,
+		who: &T::AccountId,
+		amount: BalanceOf<T>,
+		reasons: WithdrawReasons,
+	) -> DispatchResult {
+		let frozen = ReasonLocks::<T>::iter_prefix_values((who, currency_id))
+			.filter(|(_, lock_reasons)| lock_reasons.intersects(reasons))
+			.fold(Zero::zero(), |frozen: BalanceOf<T>, (locked, _)| frozen.max(locked));
+		if frozen.is_zero() {
+			return Ok(());
+		}
+		let new_balance = T::Stp258Currency::free_balance(currency_id, who)
+			.checked_sub(&amount)
+			.ok_or(Error::<T>::BalanceTooLow)?;
+		ensure!(new_balance >= frozen, Error::<T>::LiquidityRestrictions);
+		Ok(())
+	}
+
+	/// Take `amount` off the reserve named `id`, removing the entry once it
+	/// is empty.
+	fn reduce_named_reserve(
+		id: &T::ReserveIdentifier,
+		currency_id: CurrencyIdOf<T>,
+		who: &T::AccountId,
+		amount: BalanceOf<T>,
+	) {
+		ReservesNamed::<T>::mutate_exists((who, currency_id), id, |maybe_reserved| {
+			let reserved = maybe_reserved.unwrap_or_default().saturating_sub(amount);
+			*maybe_reserved = if reserved.is_zero() { None } else { Some(reserved) };
+		});
+	}
 }
 
 impl<T: Config> Stp258Currency<T::AccountId> for Pallet<T> {
@@ -161,7 +1682,7 @@ impl<T: Config> Stp258Currency<T::AccountId> for Pallet<T> {
 
 	fn base_unit(currency_id: Self::CurrencyId) -> Self::Balance {
 		if currency_id == T::GetStp258NativeId::get() {
-			T::Stp258Native::minimum_balance()
+			T::GetStp258NativeBaseUnit::get()
 		} else {
 			T::Stp258Currency::base_unit(currency_id)
 		}
@@ -203,7 +1724,8 @@ impl<T: Config> Stp258Currency<T::AccountId> for Pallet<T> {
 		if currency_id == T::GetStp258NativeId::get() {
 			T::Stp258Native::ensure_can_withdraw(who, amount)
 		} else {
-			T::Stp258Currency::ensure_can_withdraw(currency_id, who, amount)
+			T::Stp258Currency::ensure_can_withdraw(currency_id, who, amount)?;
+			Self::ensure_reason_locks(currency_id, who, amount, WithdrawReasons::all())
 		}
 	}
 
@@ -216,11 +1738,16 @@ impl<T: Config> Stp258Currency<T::AccountId> for Pallet<T> {
 		if amount.is_zero() || from == to {
 			return Ok(());
 		}
+		Self::ensure_not_paused(currency_id)?;
+		Self::ensure_not_frozen(currency_id, from)?;
+		T::PreTransfer::pre_transfer(currency_id, from, to, amount)?;
 		if currency_id == T::GetStp258NativeId::get() {
 			T::Stp258Native::transfer(from, to, amount)?;
 		} else {
+			Self::ensure_reason_locks(currency_id, from, amount, WithdrawReasons::TRANSFER)?;
 			T::Stp258Currency::transfer(currency_id, from, to, amount)?;
 		}
+		T::OnTransfer::on_transfer(currency_id, from, to, amount);
 		Self::deposit_event(Event::Transferred(currency_id, from.clone(), to.clone(), amount));
 		Ok(())
 	}
@@ -229,11 +1756,16 @@ impl<T: Config> Stp258Currency<T::AccountId> for Pallet<T> {
 		if amount.is_zero() {
 			return Ok(());
 		}
+		Self::ensure_not_paused(currency_id)?;
+		Self::ensure_within_supply_cap(currency_id, amount)?;
+		Self::ensure_within_issuance_limits(currency_id, amount, Zero::zero())?;
 		if currency_id == T::GetStp258NativeId::get() {
 			T::Stp258Native::deposit(who, amount)?;
 		} else {
 			T::Stp258Currency::deposit(currency_id, who, amount)?;
 		}
+		Self::note_issuance(currency_id, amount, Zero::zero());
+		T::OnDeposit::on_deposit(currency_id, who, amount);
 		Self::deposit_event(Event::Deposited(currency_id, who.clone(), amount));
 		Ok(())
 	}
@@ -242,11 +1774,17 @@ impl<T: Config> Stp258Currency<T::AccountId> for Pallet<T> {
 		if amount.is_zero() {
 			return Ok(());
 		}
+		Self::ensure_not_paused(currency_id)?;
+		Self::ensure_not_frozen(currency_id, who)?;
+		Self::ensure_within_issuance_limits(currency_id, Zero::zero(), amount)?;
 		if currency_id == T::GetStp258NativeId::get() {
 			T::Stp258Native::withdraw(who, amount)?;
 		} else {
+			Self::ensure_reason_locks(currency_id, who, amount, WithdrawReasons::all())?;
 			T::Stp258Currency::withdraw(currency_id, who, amount)?;
 		}
+		Self::note_issuance(currency_id, Zero::zero(), amount);
+		T::OnWithdraw::on_withdraw(currency_id, who, amount);
 		Self::deposit_event(Event::Withdrawn(currency_id, who.clone(), amount));
 		Ok(())
 	}
@@ -260,11 +1798,16 @@ impl<T: Config> Stp258Currency<T::AccountId> for Pallet<T> {
 	}
 
 	fn slash(currency_id: Self::CurrencyId, who: &T::AccountId, amount: Self::Balance) -> Self::Balance {
-		if currency_id == T::GetStp258NativeId::get() {
+		let remaining = if currency_id == T::GetStp258NativeId::get() {
 			T::Stp258Native::slash(who, amount)
 		} else {
 			T::Stp258Currency::slash(currency_id, who, amount)
+		};
+		let slashed = amount.saturating_sub(remaining);
+		if !slashed.is_zero() {
+			Self::deposit_event(Event::Slashed(currency_id, who.clone(), slashed, Zero::zero()));
 		}
+		remaining
 	}
 }
 
@@ -272,11 +1815,30 @@ impl<T: Config> Stp258CurrencyExtended<T::AccountId> for Pallet<T> {
 	type Amount = AmountOf<T>;
 
 	fn update_balance(currency_id: Self::CurrencyId, who: &T::AccountId, by_amount: Self::Amount) -> DispatchResult {
+		Self::ensure_not_paused(currency_id)?;
+		let amount: BalanceOf<T> = by_amount
+			.abs()
+			.try_into()
+			.map_err(|_| Error::<T>::AmountIntoBalanceFailed)?;
+		let (minted, burned) = if by_amount.is_positive() {
+			(amount, Zero::zero())
+		} else {
+			(Zero::zero(), amount)
+		};
+		Self::ensure_within_supply_cap(currency_id, minted)?;
+		Self::ensure_within_issuance_limits(currency_id, minted, burned)?;
 		if currency_id == T::GetStp258NativeId::get() {
 			T::Stp258Native::update_balance(who, by_amount)?;
 		} else {
 			T::Stp258Currency::update_balance(currency_id, who, by_amount)?;
 		}
+		Self::note_issuance(currency_id, minted, burned);
+		if !minted.is_zero() {
+			T::OnDeposit::on_deposit(currency_id, who, minted);
+		}
+		if !burned.is_zero() {
+			T::OnWithdraw::on_withdraw(currency_id, who, burned);
+		}
 		Self::deposit_event(Event::BalanceUpdated(currency_id, who.clone(), by_amount));
 		Ok(())
 	}
@@ -291,32 +1853,115 @@ impl<T: Config> Stp258CurrencyLockable<T::AccountId> for Pallet<T> {
 		who: &T::AccountId,
 		amount: Self::Balance,
 	) -> DispatchResult {
+		Self::set_lock_with_reasons(lock_id, currency_id, who, amount, WithdrawReasons::all())
+	}
+
+	fn extend_lock(
+		lock_id: LockIdentifier,
+		currency_id: Self::CurrencyId,
+		who: &T::AccountId,
+		amount: Self::Balance,
+	) -> DispatchResult {
+		Self::extend_lock_with_reasons(lock_id, currency_id, who, amount, WithdrawReasons::all())
+	}
+
+	fn remove_lock(lock_id: LockIdentifier, currency_id: Self::CurrencyId, who: &T::AccountId) -> DispatchResult {
 		if currency_id == T::GetStp258NativeId::get() {
-			T::Stp258Native::set_lock(lock_id, who, amount)
+			T::Stp258Native::remove_lock(lock_id, who)?;
 		} else {
-			T::Stp258Currency::set_lock(lock_id, currency_id, who, amount)
+			T::Stp258Currency::remove_lock(lock_id, currency_id, who)?;
+			ReasonLocks::<T>::remove((who, currency_id), lock_id);
 		}
+		Locks::<T>::remove((who, currency_id), lock_id);
+		Self::clear_lock_expiry(lock_id, currency_id, who);
+		Self::deposit_event(Event::LockRemoved(lock_id, currency_id, who.clone()));
+		Ok(())
 	}
+}
 
-	fn extend_lock(
+impl<T: Config> Stp258CurrencyReasonLockable<T::AccountId> for Pallet<T> {
+	fn set_lock_with_reasons(
 		lock_id: LockIdentifier,
 		currency_id: Self::CurrencyId,
 		who: &T::AccountId,
 		amount: Self::Balance,
+		reasons: WithdrawReasons,
 	) -> DispatchResult {
+		Self::clear_lock_expiry(lock_id, currency_id, who);
 		if currency_id == T::GetStp258NativeId::get() {
-			T::Stp258Native::extend_lock(lock_id, who, amount)
+			T::Stp258Native::set_lock_with_reasons(lock_id, who, amount, reasons)?;
+		} else if reasons == WithdrawReasons::all() {
+			ReasonLocks::<T>::remove((who, currency_id), lock_id);
+			T::Stp258Currency::set_lock(lock_id, currency_id, who, amount)?;
 		} else {
-			T::Stp258Currency::extend_lock(lock_id, currency_id, who, amount)
+			// `T::Stp258Currency` locks every withdrawal, so partial locks are
+			// kept here instead.
+			T::Stp258Currency::remove_lock(lock_id, currency_id, who)?;
+			ReasonLocks::<T>::insert((who, currency_id), lock_id, (amount, reasons));
 		}
+		Locks::<T>::insert((who, currency_id), lock_id, amount);
+		Self::deposit_event(Event::LockSet(lock_id, currency_id, who.clone(), amount));
+		Ok(())
 	}
 
-	fn remove_lock(lock_id: LockIdentifier, currency_id: Self::CurrencyId, who: &T::AccountId) -> DispatchResult {
+	fn extend_lock_with_reasons(
+		lock_id: LockIdentifier,
+		currency_id: Self::CurrencyId,
+		who: &T::AccountId,
+		amount: Self::Balance,
+		reasons: WithdrawReasons,
+	) -> DispatchResult {
+		if currency_id == T::GetStp258NativeId::get() {
+			T::Stp258Native::extend_lock_with_reasons(lock_id, who, amount, reasons)?;
+		} else {
+			let (amount, reasons) = match ReasonLocks::<T>::get((who, currency_id), lock_id) {
+				Some((locked, lock_reasons)) => (amount.max(locked), reasons | lock_reasons),
+				None => (amount, reasons),
+			};
+			if reasons == WithdrawReasons::all() {
+				ReasonLocks::<T>::remove((who, currency_id), lock_id);
+				T::Stp258Currency::extend_lock(lock_id, currency_id, who, amount)?;
+			} else {
+				ReasonLocks::<T>::insert((who, currency_id), lock_id, (amount, reasons));
+			}
+		}
+		let amount = Locks::<T>::get((who, currency_id), lock_id).map_or(amount, |locked| amount.max(locked));
+		Locks::<T>::insert((who, currency_id), lock_id, amount);
+		Self::deposit_event(Event::LockSet(lock_id, currency_id, who.clone(), amount));
+		Ok(())
+	}
+
+	fn ensure_can_withdraw_for(
+		currency_id: Self::CurrencyId,
+		who: &T::AccountId,
+		amount: Self::Balance,
+		reasons: WithdrawReasons,
+	) -> DispatchResult {
 		if currency_id == T::GetStp258NativeId::get() {
-			T::Stp258Native::remove_lock(lock_id, who)
+			T::Stp258Native::ensure_can_withdraw_for(who, amount, reasons)
 		} else {
-			T::Stp258Currency::remove_lock(lock_id, currency_id, who)
+			T::Stp258Currency::ensure_can_withdraw(currency_id, who, amount)?;
+			Self::ensure_reason_locks(currency_id, who, amount, reasons)
+		}
+	}
+
+	fn withdraw_for(
+		currency_id: Self::CurrencyId,
+		who: &T::AccountId,
+		amount: Self::Balance,
+		reasons: WithdrawReasons,
+	) -> DispatchResult {
+		if amount.is_zero() {
+			return Ok(());
 		}
+		Self::ensure_not_paused(currency_id)?;
+		Self::ensure_not_frozen(currency_id, who)?;
+		Self::ensure_within_issuance_limits(currency_id, Zero::zero(), amount)?;
+		Self::do_withdraw_for(currency_id, who, amount, reasons)?;
+		Self::note_issuance(currency_id, Zero::zero(), amount);
+		T::OnWithdraw::on_withdraw(currency_id, who, amount);
+		Self::deposit_event(Event::Withdrawn(currency_id, who.clone(), amount));
+		Ok(())
 	}
 }
 
@@ -330,11 +1975,16 @@ impl<T: Config> Stp258CurrencyReservable<T::AccountId> for Pallet<T> {
 	}
 
 	fn slash_reserved(currency_id: Self::CurrencyId, who: &T::AccountId, value: Self::Balance) -> Self::Balance {
-		if currency_id == T::GetStp258NativeId::get() {
+		let remaining = if currency_id == T::GetStp258NativeId::get() {
 			T::Stp258Native::slash_reserved(who, value)
 		} else {
 			T::Stp258Currency::slash_reserved(currency_id, who, value)
+		};
+		let slashed = value.saturating_sub(remaining);
+		if !slashed.is_zero() {
+			Self::deposit_event(Event::Slashed(currency_id, who.clone(), Zero::zero(), slashed));
 		}
+		remaining
 	}
 
 	fn reserved_balance(currency_id: Self::CurrencyId, who: &T::AccountId) -> Self::Balance {
@@ -346,19 +1996,31 @@ impl<T: Config> Stp258CurrencyReservable<T::AccountId> for Pallet<T> {
 	}
 
 	fn reserve(currency_id: Self::CurrencyId, who: &T::AccountId, value: Self::Balance) -> DispatchResult {
+		if value.is_zero() {
+			return Ok(());
+		}
+		Self::ensure_not_frozen(currency_id, who)?;
 		if currency_id == T::GetStp258NativeId::get() {
-			T::Stp258Native::reserve(who, value)
+			T::Stp258Native::reserve(who, value)?;
 		} else {
-			T::Stp258Currency::reserve(currency_id, who, value)
+			Self::ensure_reason_locks(currency_id, who, value, WithdrawReasons::RESERVE)?;
+			T::Stp258Currency::reserve(currency_id, who, value)?;
 		}
+		Self::deposit_event(Event::Reserved(currency_id, who.clone(), value));
+		Ok(())
 	}
 
 	fn unreserve(currency_id: Self::CurrencyId, who: &T::AccountId, value: Self::Balance) -> Self::Balance {
-		if currency_id == T::GetStp258NativeId::get() {
+		let remaining = if currency_id == T::GetStp258NativeId::get() {
 			T::Stp258Native::unreserve(who, value)
 		} else {
 			T::Stp258Currency::unreserve(currency_id, who, value)
+		};
+		let unreserved = value.saturating_sub(remaining);
+		if !unreserved.is_zero() {
+			Self::deposit_event(Event::Unreserved(currency_id, who.clone(), unreserved));
 		}
+		remaining
 	}
 
 	fn repatriate_reserved(
@@ -368,11 +2030,120 @@ impl<T: Config> Stp258CurrencyReservable<T::AccountId> for Pallet<T> {
 		value: Self::Balance,
 		status: BalanceStatus,
 	) -> result::Result<Self::Balance, DispatchError> {
-		if currency_id == T::GetStp258NativeId::get() {
-			T::Stp258Native::repatriate_reserved(slashed, beneficiary, value, status)
+		Self::ensure_not_frozen(currency_id, slashed)?;
+		let remaining = if currency_id == T::GetStp258NativeId::get() {
+			T::Stp258Native::repatriate_reserved(slashed, beneficiary, value, status)?
 		} else {
-			T::Stp258Currency::repatriate_reserved(currency_id, slashed, beneficiary, value, status)
+			T::Stp258Currency::repatriate_reserved(currency_id, slashed, beneficiary, value, status)?
+		};
+		let repatriated = value.saturating_sub(remaining);
+		if !repatriated.is_zero() {
+			Self::deposit_event(Event::ReserveRepatriated(
+				currency_id,
+				slashed.clone(),
+				beneficiary.clone(),
+				repatriated,
+				status,
+			));
+		}
+		Ok(remaining)
+	}
+}
+
+impl<T: Config> Stp258CurrencyNamedReservable<T::AccountId> for Pallet<T> {
+	type ReserveIdentifier = T::ReserveIdentifier;
+
+	fn reserved_balance_named(
+		id: &Self::ReserveIdentifier,
+		currency_id: Self::CurrencyId,
+		who: &T::AccountId,
+	) -> Self::Balance {
+		Self::reserves_named((who, currency_id), id)
+	}
+
+	fn reserve_named(
+		id: &Self::ReserveIdentifier,
+		currency_id: Self::CurrencyId,
+		who: &T::AccountId,
+		value: Self::Balance,
+	) -> DispatchResult {
+		if value.is_zero() {
+			return Ok(());
+		}
+		<Self as Stp258CurrencyReservable<T::AccountId>>::reserve(currency_id, who, value)?;
+		ReservesNamed::<T>::mutate((who, currency_id), id, |reserved| *reserved = reserved.saturating_add(value));
+		Ok(())
+	}
+
+	fn unreserve_named(
+		id: &Self::ReserveIdentifier,
+		currency_id: Self::CurrencyId,
+		who: &T::AccountId,
+		value: Self::Balance,
+	) -> Self::Balance {
+		let to_change = value.min(Self::reserves_named((who, currency_id), id));
+		let remaining = <Self as Stp258CurrencyReservable<T::AccountId>>::unreserve(currency_id, who, to_change);
+		let actual = to_change.saturating_sub(remaining);
+		Self::reduce_named_reserve(id, currency_id, who, actual);
+		value.saturating_sub(actual)
+	}
+
+	fn slash_reserved_named(
+		id: &Self::ReserveIdentifier,
+		currency_id: Self::CurrencyId,
+		who: &T::AccountId,
+		value: Self::Balance,
+	) -> Self::Balance {
+		let to_change = value.min(Self::reserves_named((who, currency_id), id));
+		let remaining = <Self as Stp258CurrencyReservable<T::AccountId>>::slash_reserved(currency_id, who, to_change);
+		let actual = to_change.saturating_sub(remaining);
+		Self::reduce_named_reserve(id, currency_id, who, actual);
+		value.saturating_sub(actual)
+	}
+
+	fn repatriate_reserved_named(
+		id: &Self::ReserveIdentifier,
+		currency_id: Self::CurrencyId,
+		slashed: &T::AccountId,
+		beneficiary: &T::AccountId,
+		value: Self::Balance,
+		status: BalanceStatus,
+	) -> result::Result<Self::Balance, DispatchError> {
+		if slashed == beneficiary {
+			return match status {
+				BalanceStatus::Free => Ok(Self::unreserve_named(id, currency_id, slashed, value)),
+				BalanceStatus::Reserved => {
+					Ok(value.saturating_sub(Self::reserves_named((slashed, currency_id), id)))
+				}
+			};
+		}
+
+		let to_change = value.min(Self::reserves_named((slashed, currency_id), id));
+		let remaining = <Self as Stp258CurrencyReservable<T::AccountId>>::repatriate_reserved(
+			currency_id,
+			slashed,
+			beneficiary,
+			to_change,
+			status,
+		)?;
+		let actual = to_change.saturating_sub(remaining);
+		Self::reduce_named_reserve(id, currency_id, slashed, actual);
+		if status == BalanceStatus::Reserved {
+			ReservesNamed::<T>::mutate((beneficiary, currency_id), id, |reserved| {
+				*reserved = reserved.saturating_add(actual)
+			});
 		}
+		Ok(value.saturating_sub(actual))
+	}
+
+	fn unreserve_all_named(
+		id: &Self::ReserveIdentifier,
+		currency_id: Self::CurrencyId,
+		who: &T::AccountId,
+	) -> Self::Balance {
+		let value = Self::reserves_named((who, currency_id), id);
+		let remaining = Self::unreserve_named(id, currency_id, who, value);
+		value.saturating_sub(remaining)
 	}
 }
 
@@ -458,6 +2229,60 @@ where
 	}
 }
 
+impl<T, GetCurrencyId> Stp258AssetReasonLockable<T::AccountId> for Currency<T, GetCurrencyId>
+where
+	T: Config,
+	GetCurrencyId: Get<CurrencyIdOf<T>>,
+{
+	fn set_lock_with_reasons(
+		lock_id: LockIdentifier,
+		who: &T::AccountId,
+		amount: Self::Balance,
+		reasons: WithdrawReasons,
+	) -> DispatchResult {
+		<Pallet<T> as Stp258CurrencyReasonLockable<T::AccountId>>::set_lock_with_reasons(
+			lock_id,
+			GetCurrencyId::get(),
+			who,
+			amount,
+			reasons,
+		)
+	}
+
+	fn extend_lock_with_reasons(
+		lock_id: LockIdentifier,
+		who: &T::AccountId,
+		amount: Self::Balance,
+		reasons: WithdrawReasons,
+	) -> DispatchResult {
+		<Pallet<T> as Stp258CurrencyReasonLockable<T::AccountId>>::extend_lock_with_reasons(
+			lock_id,
+			GetCurrencyId::get(),
+			who,
+			amount,
+			reasons,
+		)
+	}
+
+	fn ensure_can_withdraw_for(who: &T::AccountId, amount: Self::Balance, reasons: WithdrawReasons) -> DispatchResult {
+		<Pallet<T> as Stp258CurrencyReasonLockable<T::AccountId>>::ensure_can_withdraw_for(
+			GetCurrencyId::get(),
+			who,
+			amount,
+			reasons,
+		)
+	}
+
+	fn withdraw_for(who: &T::AccountId, amount: Self::Balance, reasons: WithdrawReasons) -> DispatchResult {
+		<Pallet<T> as Stp258CurrencyReasonLockable<T::AccountId>>::withdraw_for(
+			GetCurrencyId::get(),
+			who,
+			amount,
+			reasons,
+		)
+	}
+}
+
 impl<T, GetCurrencyId> Stp258AssetReservable<T::AccountId> for Currency<T, GetCurrencyId>
 where
 	T: Config,
@@ -499,6 +2324,56 @@ where
 	}
 }
 
+impl<T, GetCurrencyId> Stp258AssetNamedReservable<T::AccountId> for Currency<T, GetCurrencyId>
+where
+	T: Config,
+	GetCurrencyId: Get<CurrencyIdOf<T>>,
+{
+	type ReserveIdentifier = T::ReserveIdentifier;
+
+	fn reserved_balance_named(id: &Self::ReserveIdentifier, who: &T::AccountId) -> Self::Balance {
+		<Pallet<T> as Stp258CurrencyNamedReservable<T::AccountId>>::reserved_balance_named(id, GetCurrencyId::get(), who)
+	}
+
+	fn reserve_named(id: &Self::ReserveIdentifier, who: &T::AccountId, value: Self::Balance) -> DispatchResult {
+		<Pallet<T> as Stp258CurrencyNamedReservable<T::AccountId>>::reserve_named(id, GetCurrencyId::get(), who, value)
+	}
+
+	fn unreserve_named(id: &Self::ReserveIdentifier, who: &T::AccountId, value: Self::Balance) -> Self::Balance {
+		<Pallet<T> as Stp258CurrencyNamedReservable<T::AccountId>>::unreserve_named(id, GetCurrencyId::get(), who, value)
+	}
+
+	fn slash_reserved_named(id: &Self::ReserveIdentifier, who: &T::AccountId, value: Self::Balance) -> Self::Balance {
+		<Pallet<T> as Stp258CurrencyNamedReservable<T::AccountId>>::slash_reserved_named(
+			id,
+			GetCurrencyId::get(),
+			who,
+			value,
+		)
+	}
+
+	fn repatriate_reserved_named(
+		id: &Self::ReserveIdentifier,
+		slashed: &T::AccountId,
+		beneficiary: &T::AccountId,
+		value: Self::Balance,
+		status: BalanceStatus,
+	) -> result::Result<Self::Balance, DispatchError> {
+		<Pallet<T> as Stp258CurrencyNamedReservable<T::AccountId>>::repatriate_reserved_named(
+			id,
+			GetCurrencyId::get(),
+			slashed,
+			beneficiary,
+			value,
+			status,
+		)
+	}
+
+	fn unreserve_all_named(id: &Self::ReserveIdentifier, who: &T::AccountId) -> Self::Balance {
+		<Pallet<T> as Stp258CurrencyNamedReservable<T::AccountId>>::unreserve_all_named(id, GetCurrencyId::get(), who)
+	}
+}
+
 pub type Stp258NativeOf<T> = Currency<T, <T as Config>::GetStp258NativeId>;
 
 /// Adapt other currency traits implementation to `Stp258Asset`.
@@ -618,6 +2493,46 @@ where
 	}
 }
 
+// Adapt `frame_support::traits::LockableCurrency`
+impl<T, AccountId, Currency, Amount, Moment> Stp258AssetReasonLockable<AccountId>
+	for Stp258AssetAdapter<T, Currency, Amount, Moment>
+where
+	Currency: SetheumLockableCurrency<AccountId>,
+	T: Config,
+{
+	fn set_lock_with_reasons(
+		lock_id: LockIdentifier,
+		who: &AccountId,
+		amount: Self::Balance,
+		reasons: WithdrawReasons,
+	) -> DispatchResult {
+		Currency::set_lock(lock_id, who, amount, reasons);
+		Ok(())
+	}
+
+	fn extend_lock_with_reasons(
+		lock_id: LockIdentifier,
+		who: &AccountId,
+		amount: Self::Balance,
+		reasons: WithdrawReasons,
+	) -> DispatchResult {
+		Currency::extend_lock(lock_id, who, amount, reasons);
+		Ok(())
+	}
+
+	fn ensure_can_withdraw_for(who: &AccountId, amount: Self::Balance, reasons: WithdrawReasons) -> DispatchResult {
+		let new_balance = Self::free_balance(who)
+			.checked_sub(&amount)
+			.ok_or(Error::<T>::BalanceTooLow)?;
+
+		Currency::ensure_can_withdraw(who, amount, reasons, new_balance)
+	}
+
+	fn withdraw_for(who: &AccountId, amount: Self::Balance, reasons: WithdrawReasons) -> DispatchResult {
+		Currency::withdraw(who, amount, reasons, ExistenceRequirement::KeepAlive).map(|_| ())
+	}
+}
+
 // Adapt `frame_support::traits::ReservableCurrency`
 impl<T, AccountId, Currency, Amount, Moment> Stp258AssetReservable<AccountId>
 	for Stp258AssetAdapter<T, Currency, Amount, Moment>
@@ -658,9 +2573,35 @@ where
 
 impl<T: Config> MergeAccount<T::AccountId> for Pallet<T> {
 	fn merge_account(source: &T::AccountId, dest: &T::AccountId) -> DispatchResult {
+		ensure!(
+			FrozenAccounts::<T>::iter_prefix(source).next().is_none(),
+			Error::<T>::AccountFrozen
+		);
+		let currency_ids: Vec<CurrencyIdOf<T>> =
+			CurrencyRegistry::<T>::iter().map(|(currency_id, _)| currency_id).collect();
+		// the backend only knows of the locks it holds itself
+		for &currency_id in &currency_ids {
+			let free_balance = <Self as Stp258Currency<T::AccountId>>::free_balance(currency_id, source);
+			Self::ensure_reason_locks(currency_id, source, free_balance, WithdrawReasons::TRANSFER)?;
+		}
 		with_transaction_result(|| {
 			// transfer non-native free to dest
 			T::Stp258Currency::merge_account(source, dest)?;
+			T::Stp258Native::ensure_can_withdraw_for(
+				source,
+				T::Stp258Native::free_balance(source),
+				WithdrawReasons::TRANSFER,
+			)?;
+
+			// drop every lock left on source
+			for &currency_id in &currency_ids {
+				let lock_ids: Vec<LockIdentifier> = Locks::<T>::iter_prefix((source, currency_id))
+					.map(|(lock_id, _)| lock_id)
+					.collect();
+				for lock_id in lock_ids {
+					<Self as Stp258CurrencyLockable<T::AccountId>>::remove_lock(lock_id, currency_id, source)?;
+				}
+			}
 
 			// unreserve all reserved currency
 			T::Stp258Native::unreserve(source, T::Stp258Native::reserved_balance(source));
@@ -3,14 +3,19 @@
 #![cfg(test)]
 
 use super::*;
-use frame_support::{construct_runtime, parameter_types};
+use frame_support::{
+	construct_runtime, parameter_types,
+	weights::{constants::RocksDbWeight, IdentityFee},
+};
+use frame_system::EnsureRoot;
 use serp_traits::parameter_type_with_key;
 use sp_core::H256;
 use sp_runtime::{
 	testing::Header,
 	traits::{AccountIdConversion, IdentityLookup},
-	AccountId32, ModuleId, Perbill,
+	AccountId32, DispatchError, FixedPointNumber, ModuleId, Perbill,
 };
+use std::cell::RefCell;
 
 use crate as stp258_currencies;
 
@@ -38,7 +43,7 @@ impl frame_system::Config for Runtime {
 	type AccountData = pallet_balances::AccountData<u64>;
 	type OnNewAccount = ();
 	type OnKilledAccount = ();
-	type DbWeight = ();
+	type DbWeight = RocksDbWeight;
 	type BaseCallFilter = ();
 	type SystemWeightInfo = ();
 	type SS58Prefix = ();
@@ -46,6 +51,7 @@ impl frame_system::Config for Runtime {
 
 type CurrencyId = u32;
 type Balance = u64;
+type ReserveIdentifier = [u8; 8];
 type Blocknumber = u64;
 
 parameter_types! {
@@ -126,8 +132,84 @@ pub const JUSD: CurrencyId = 3;
 
 pub const ADJUSTMENT_FREQUENCY: Blocknumber = 10;
 
+parameter_types! {
+	pub const GetSett: CurrencyId = SETT;
+}
+
 parameter_types! {
 	pub const GetStp258NativeId: CurrencyId = DNAR;
+	pub const GetStp258NativeBaseUnit: Balance = 1_000;
+	pub const MaxExpiringLocksPerBlock: u32 = 2;
+	pub const MaxVestingSchedules: u32 = 3;
+	pub const IssuanceWindow: u64 = 10;
+	pub const MaxBatchTransfers: u32 = 3;
+	pub const GetStp258BenchmarkCurrencyId: CurrencyId = SETT;
+}
+
+thread_local! {
+	pub static VETOED_SENDER: RefCell<Option<AccountId>> = RefCell::new(None);
+	pub static OBSERVED_MOVEMENTS: RefCell<Vec<(&'static str, CurrencyId, AccountId, Balance)>> = RefCell::new(vec![]);
+}
+
+/// Vetoes transfers from `VETOED_SENDER` and records every movement in
+/// `OBSERVED_MOVEMENTS`.
+pub struct MockTransferHooks;
+
+impl PreTransfer<AccountId, CurrencyId, Balance> for MockTransferHooks {
+	fn pre_transfer(_: CurrencyId, from: &AccountId, _: &AccountId, _: Balance) -> DispatchResult {
+		if VETOED_SENDER.with(|v| v.borrow().as_ref() == Some(from)) {
+			return Err(DispatchError::Other("transfer vetoed"));
+		}
+		Ok(())
+	}
+}
+
+impl OnTransfer<AccountId, CurrencyId, Balance> for MockTransferHooks {
+	fn on_transfer(currency_id: CurrencyId, from: &AccountId, _: &AccountId, amount: Balance) {
+		OBSERVED_MOVEMENTS.with(|v| v.borrow_mut().push(("transfer", currency_id, from.clone(), amount)));
+	}
+}
+
+impl OnDeposit<AccountId, CurrencyId, Balance> for MockTransferHooks {
+	fn on_deposit(currency_id: CurrencyId, who: &AccountId, amount: Balance) {
+		OBSERVED_MOVEMENTS.with(|v| v.borrow_mut().push(("deposit", currency_id, who.clone(), amount)));
+	}
+}
+
+impl OnWithdraw<AccountId, CurrencyId, Balance> for MockTransferHooks {
+	fn on_withdraw(currency_id: CurrencyId, who: &AccountId, amount: Balance) {
+		OBSERVED_MOVEMENTS.with(|v| v.borrow_mut().push(("withdraw", currency_id, who.clone(), amount)));
+	}
+}
+
+/// Prices DNAR at two dollars, SETT and JUSD at one dollar.
+pub struct MockPriceProvider;
+
+impl PriceProvider<CurrencyId> for MockPriceProvider {
+	fn get_price(base: CurrencyId, quote: CurrencyId) -> Option<Price> {
+		let usd_cents = |currency_id| match currency_id {
+			DNAR => Some(200u128),
+			SETT | JUSD => Some(100u128),
+			_ => None,
+		};
+		Price::checked_from_rational(usd_cents(base)?, usd_cents(quote)?)
+	}
+}
+
+parameter_types! {
+	pub LiquidityAccount: AccountId = ModuleId(*b"dsss/liq").into_account();
+	pub FeeReceiver: AccountId = ModuleId(*b"dsss/fee").into_account();
+}
+
+parameter_types! {
+	pub const TransactionByteFee: Balance = 1;
+}
+
+impl pallet_transaction_payment::Config for Runtime {
+	type OnChargeTransaction = ChargeStp258Fee<Runtime, FeeReceiver>;
+	type TransactionByteFee = TransactionByteFee;
+	type WeightToFee = IdentityFee<Balance>;
+	type FeeMultiplierUpdate = ();
 }
 
 impl Config for Runtime {
@@ -135,6 +217,24 @@ impl Config for Runtime {
 	type Stp258Currency = Stp258Serp;
 	type Stp258Native = AdaptedStp258Asset;
 	type GetStp258NativeId = GetStp258NativeId;
+	type GetStp258NativeBaseUnit = GetStp258NativeBaseUnit;
+	type ReserveIdentifier = ReserveIdentifier;
+	type MaxExpiringLocksPerBlock = MaxExpiringLocksPerBlock;
+	type MaxVestingSchedules = MaxVestingSchedules;
+	type MaxBatchTransfers = MaxBatchTransfers;
+	type IssuanceWindow = IssuanceWindow;
+	type PreTransfer = MockTransferHooks;
+	type OnTransfer = MockTransferHooks;
+	type OnDeposit = MockTransferHooks;
+	type OnWithdraw = MockTransferHooks;
+	type PriceProvider = MockPriceProvider;
+	type LiquidityAccount = LiquidityAccount;
+	type UpdateOrigin = EnsureRoot<AccountId>;
+	type RegistryOrigin = EnsureRoot<AccountId>;
+	type PauseOrigin = EnsureRoot<AccountId>;
+	type FreezeOrigin = EnsureRoot<AccountId>;
+	#[cfg(feature = "runtime-benchmarks")]
+	type GetStp258BenchmarkCurrencyId = GetStp258BenchmarkCurrencyId;
 	type WeightInfo = ();
 }
 pub type Stp258Native = Stp258NativeOf<Runtime>;
@@ -150,9 +250,10 @@ construct_runtime!(
 		UncheckedExtrinsic = UncheckedExtrinsic,
 	{
 		System: frame_system::{Module, Call, Storage, Config, Event<T>},
-		Stp258Currencies: stp258_currencies::{Module, Call, Event<T>},
+		Stp258Currencies: stp258_currencies::{Module, Call, Storage, Config<T>, Event<T>},
 		Stp258Serp: stp258_serp::{Module, Storage, Event<T>, Config<T>},
 		PalletBalances: pallet_balances::{Module, Call, Storage, Config<T>, Event<T>},
+		TransactionPayment: pallet_transaction_payment::{Module, Storage},
 	}
 );
 
@@ -162,15 +263,33 @@ pub const BOB: AccountId = AccountId32::new([1u8; 32]);
 pub const SERPER: AccountId = AccountId32::new([3u8; 32]);
 pub const SETTPAY: AccountId = AccountId32::new([4u8; 32]);
 pub const ID_1: LockIdentifier = *b"1       ";
+pub const ID_2: LockIdentifier = *b"2       ";
+pub const RID_1: ReserveIdentifier = *b"1       ";
+pub const RID_2: ReserveIdentifier = *b"2       ";
+
+pub fn metadata(symbol: &[u8], decimals: u8) -> CurrencyMetadata {
+	CurrencyMetadata {
+		name: symbol.to_vec(),
+		symbol: symbol.to_vec(),
+		decimals,
+		..Default::default()
+	}
+}
 
 pub struct ExtBuilder {
 	endowed_accounts: Vec<(AccountId, CurrencyId, Balance)>,
+	registered_currencies: Vec<(CurrencyId, CurrencyMetadata)>,
 }
 
 impl Default for ExtBuilder {
 	fn default() -> Self {
 		Self {
 			endowed_accounts: vec![],
+			registered_currencies: vec![
+				(DNAR, metadata(b"DNAR", 3)),
+				(SETT, metadata(b"SETT", 4)),
+				(JUSD, metadata(b"JUSD", 3)),
+			],
 		}
 	}
 }
@@ -181,6 +300,11 @@ impl ExtBuilder {
 		self
 	}
 
+	pub fn registered_currencies(mut self, registered_currencies: Vec<(CurrencyId, CurrencyMetadata)>) -> Self {
+		self.registered_currencies = registered_currencies;
+		self
+	}
+
 	pub fn one_hundred_for_alice_n_bob_n_serper_n_settpay(self) -> Self {
 		self.balances(vec![
 			(ALICE, DNAR, 100), 
@@ -203,24 +327,10 @@ impl ExtBuilder {
 			.build_storage::<Runtime>()
 			.unwrap();
 
-		pallet_balances::GenesisConfig::<Runtime> {
-			balances: self
-				.endowed_accounts
-				.clone()
-				.into_iter()
-				.filter(|(_, currency_id, _)| *currency_id == DNAR)
-				.map(|(account_id, _, initial_balance)| (account_id, initial_balance))
-				.collect::<Vec<_>>(),
-		}
-		.assimilate_storage(&mut t)
-		.unwrap();
-
-		stp258_serp::GenesisConfig::<Runtime> {
-			endowed_accounts: self
-				.endowed_accounts
-				.into_iter()
-				.filter(|(_, currency_id, _)| *currency_id != DNAR)
-				.collect::<Vec<_>>(),
+		stp258_currencies::GenesisConfig::<Runtime> {
+			currencies: self.registered_currencies,
+			balances: self.endowed_accounts,
+			..Default::default()
 		}
 		.assimilate_storage(&mut t)
 		.unwrap();
@@ -0,0 +1,65 @@
+//! Currency registry metadata for the Stp258 currencies module.
+
+use codec::{Decode, Encode};
+#[cfg(feature = "std")]
+use serde::{Deserialize, Serialize};
+use sp_runtime::RuntimeDebug;
+use sp_std::vec::Vec;
+
+/// Whether a registered currency can still be issued.
+#[derive(Clone, Copy, Encode, Decode, PartialEq, Eq, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub enum CurrencyStatus {
+	/// The currency can be moved and issued.
+	Active,
+	/// The currency can still be moved, but no longer issued.
+	Deprecated,
+}
+
+impl Default for CurrencyStatus {
+	fn default() -> Self {
+		CurrencyStatus::Active
+	}
+}
+
+/// Metadata of a registered currency.
+#[derive(Clone, Default, Encode, Decode, PartialEq, Eq, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub struct CurrencyMetadata {
+	/// The name of the currency, e.g. `Setter`.
+	pub name: Vec<u8>,
+	/// The ticker symbol of the currency, e.g. `SETT`.
+	pub symbol: Vec<u8>,
+	/// The number of decimals of one whole unit.
+	pub decimals: u8,
+	/// What the currency is pegged to, e.g. `USD`, if anything.
+	pub peg: Option<Vec<u8>>,
+	/// Whether this is the native currency. Always derived from
+	/// `GetStp258NativeId` on registration.
+	pub is_native: bool,
+	/// Whether the currency can still be issued.
+	pub status: CurrencyStatus,
+}
+
+/// The accounts entitled to manage a currency.
+#[derive(Clone, Encode, Decode, PartialEq, Eq, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub struct CurrencyRoles<AccountId> {
+	/// May mint and burn the currency.
+	pub issuer: AccountId,
+	/// May freeze and thaw accounts holding the currency.
+	pub freezer: AccountId,
+	/// May hand the roles over to other accounts.
+	pub admin: AccountId,
+}
+
+/// The most of a currency that can be minted and burned per issuance
+/// window.
+#[derive(Clone, Default, Encode, Decode, PartialEq, Eq, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub struct IssuanceLimits<Balance> {
+	/// The amount that can be minted per window.
+	pub mint: Balance,
+	/// The amount that can be burned per window.
+	pub burn: Balance,
+}
@@ -3,8 +3,13 @@
 #![cfg(test)]
 
 use super::*;
-use frame_support::{assert_noop, assert_ok};
+use frame_support::{
+	assert_noop, assert_ok,
+	traits::{OnInitialize, WithdrawReasons},
+	weights::GetDispatchInfo,
+};
 use mock::{Event, *};
+use pallet_transaction_payment::OnChargeTransaction;
 use sp_runtime::traits::BadOrigin;
 
 #[test]
@@ -86,6 +91,16 @@ fn stp258_asset_adapting_pallet_balances_reservable() {
 		});
 }
 
+#[test]
+fn base_unit_should_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_eq!(Stp258Currencies::base_unit(DNAR), 1_000);
+		assert_eq!(Stp258Currencies::base_unit(SETT), 10_000);
+		assert_eq!(Stp258Currencies::base_unit(JUSD), 1_000);
+		assert_eq!(Stp258Currencies::minimum_balance(DNAR), 1);
+	});
+}
+
 #[test]
 fn stp258_currency_should_work() {
 	ExtBuilder::default()
@@ -98,6 +113,121 @@ fn stp258_currency_should_work() {
 		});
 }
 
+#[test]
+fn no_op_transfers_refund_weight() {
+	ExtBuilder::default()
+		.one_hundred_for_alice_n_bob_n_serper_n_settpay()
+		.build()
+		.execute_with(|| {
+			System::set_block_number(1);
+
+			// only the currency registry is read
+			let registry_read = Some(<Runtime as frame_system::Config>::DbWeight::get().reads(1));
+			let post_info = Stp258Currencies::transfer(Some(ALICE).into(), BOB, SETT, 0).unwrap();
+			assert_eq!(post_info.actual_weight, registry_read);
+			let post_info = Stp258Currencies::transfer(Some(ALICE).into(), ALICE, SETT, 10 * 10_000).unwrap();
+			assert_eq!(post_info.actual_weight, registry_read);
+			let post_info = Stp258Currencies::transfer_native_currency(Some(ALICE).into(), BOB, 0).unwrap();
+			assert_eq!(post_info.actual_weight, registry_read);
+			let post_info = Stp258Currencies::transfer_native_currency(Some(ALICE).into(), ALICE, 10).unwrap();
+			assert_eq!(post_info.actual_weight, registry_read);
+			let post_info = Stp258Currencies::transfer_keep_alive(Some(ALICE).into(), BOB, SETT, 0).unwrap();
+			assert_eq!(post_info.actual_weight, registry_read);
+
+			assert_eq!(Stp258Currencies::free_balance(SETT, &ALICE), 100 * 10_000);
+			assert_eq!(Stp258Native::free_balance(&ALICE), 100);
+			assert!(System::events().is_empty());
+
+			let post_info = Stp258Currencies::transfer(Some(ALICE).into(), BOB, SETT, 10 * 10_000).unwrap();
+			assert_eq!(post_info.actual_weight, None);
+		});
+}
+
+#[test]
+fn transfer_keep_alive_should_work() {
+	ExtBuilder::default()
+		.one_hundred_for_alice_n_bob_n_serper_n_settpay()
+		.build()
+		.execute_with(|| {
+			assert_noop!(
+				Stp258Currencies::transfer_keep_alive(Some(ALICE).into(), BOB, DNAR, 100),
+				Error::<Runtime>::KeepAlive
+			);
+			assert_ok!(Stp258Currencies::transfer_keep_alive(Some(ALICE).into(), BOB, DNAR, 99));
+			assert_eq!(Stp258Native::free_balance(&ALICE), 1);
+			assert_eq!(Stp258Native::free_balance(&BOB), 199);
+
+			assert_ok!(Stp258Currencies::transfer_keep_alive(Some(ALICE).into(), BOB, SETT, 100 * 10_000));
+			assert_eq!(Stp258Currencies::free_balance(SETT, &ALICE), 0);
+			assert_eq!(Stp258Currencies::free_balance(SETT, &BOB), 200 * 10_000);
+		});
+}
+
+#[test]
+fn transfer_all_should_work() {
+	ExtBuilder::default()
+		.one_hundred_for_alice_n_bob_n_serper_n_settpay()
+		.build()
+		.execute_with(|| {
+			assert_ok!(Stp258Currencies::transfer_all(Some(ALICE).into(), BOB, DNAR, true));
+			assert_eq!(Stp258Native::free_balance(&ALICE), 1);
+			assert_eq!(Stp258Native::free_balance(&BOB), 199);
+
+			assert_ok!(Stp258Currencies::transfer_all(Some(ALICE).into(), BOB, DNAR, false));
+			assert_eq!(Stp258Native::free_balance(&ALICE), 0);
+			assert_eq!(Stp258Native::free_balance(&BOB), 200);
+
+			assert_ok!(Stp258Currencies::reserve(SETT, &ALICE, 30 * 10_000));
+			assert_ok!(Stp258Currencies::transfer_all(Some(ALICE).into(), BOB, SETT, false));
+			assert_eq!(Stp258Currencies::free_balance(SETT, &ALICE), 0);
+			assert_eq!(Stp258Currencies::reserved_balance(SETT, &ALICE), 30 * 10_000);
+			assert_eq!(Stp258Currencies::free_balance(SETT, &BOB), 170 * 10_000);
+		});
+}
+
+#[test]
+fn transfer_all_leaves_locked_balance() {
+	ExtBuilder::default()
+		.one_hundred_for_alice_n_bob_n_serper_n_settpay()
+		.build()
+		.execute_with(|| {
+			assert_ok!(Stp258Currencies::set_lock(ID_1, DNAR, &ALICE, 40));
+			assert_ok!(Stp258Currencies::transfer_all(Some(ALICE).into(), BOB, DNAR, false));
+			assert_eq!(Stp258Native::free_balance(&ALICE), 40);
+			assert_eq!(Stp258Native::free_balance(&BOB), 160);
+			// looking for a reducible balance is charged even if there is none
+			let post_info = Stp258Currencies::transfer_all(Some(ALICE).into(), BOB, DNAR, false).unwrap();
+			assert_eq!(post_info.actual_weight, None);
+			assert_eq!(Stp258Native::free_balance(&ALICE), 40);
+
+			// a transfer lock kept here and a lock set on the backend directly
+			assert_ok!(Stp258Currencies::set_lock_with_reasons(
+				ID_1,
+				SETT,
+				&ALICE,
+				20 * 10_000,
+				WithdrawReasons::TRANSFER
+			));
+			assert_ok!(Stp258Serp::set_lock(ID_2, SETT, &ALICE, 30 * 10_000));
+			assert_eq!(Stp258Currencies::reducible_balance(SETT, &ALICE, false), 70 * 10_000);
+			assert_ok!(Stp258Currencies::transfer_all(Some(ALICE).into(), BOB, SETT, false));
+			assert_eq!(Stp258Currencies::free_balance(SETT, &ALICE), 30 * 10_000);
+			assert_eq!(Stp258Currencies::free_balance(SETT, &BOB), 170 * 10_000);
+
+			// locks on other reasons do not hold transfers back
+			assert_ok!(Stp258Currencies::set_lock_with_reasons(
+				ID_1,
+				JUSD,
+				&ALICE,
+				100 * 1_000,
+				WithdrawReasons::RESERVE
+			));
+			assert_ok!(Stp258Currencies::transfer_all(Some(ALICE).into(), BOB, JUSD, false));
+			assert_eq!(Stp258Currencies::free_balance(JUSD, &ALICE), 0);
+			assert_eq!(Stp258Currencies::free_balance(JUSD, &BOB), 200 * 1_000);
+		});
+}
+
 #[test]
 fn stp258_currency_extended_should_work() {
 	ExtBuilder::default()
@@ -231,6 +361,42 @@ fn update_balance_call_should_work() {
 		});
 }
 
+#[test]
+fn update_balance_call_refunds_native_weight() {
+	ExtBuilder::default()
+		.one_hundred_for_alice_n_bob_n_serper_n_settpay()
+		.build()
+		.execute_with(|| {
+			let native_call = mock::Call::Stp258Currencies(crate::Call::update_balance(ALICE, DNAR, 10));
+			assert_eq!(
+				native_call.get_dispatch_info().weight,
+				<() as WeightInfo>::update_balance_native_currency_creating()
+					.max(<() as WeightInfo>::update_balance_native_currency_killing())
+			);
+			let non_native_call = mock::Call::Stp258Currencies(crate::Call::update_balance(ALICE, SETT, 10));
+			assert_eq!(
+				non_native_call.get_dispatch_info().weight,
+				<() as WeightInfo>::update_balance_non_native_currency()
+			);
+
+			let post_info = Stp258Currencies::update_balance(Origin::root(), ALICE, DNAR, 10).unwrap();
+			assert_eq!(
+				post_info.actual_weight,
+				Some(<() as WeightInfo>::update_balance_native_currency_creating())
+			);
+
+			let post_info = Stp258Currencies::update_balance(Origin::root(), ALICE, DNAR, -110).unwrap();
+			assert_eq!(
+				post_info.actual_weight,
+				Some(<() as WeightInfo>::update_balance_native_currency_killing())
+			);
+			assert_eq!(Stp258Native::free_balance(&ALICE), 0);
+
+			let post_info = Stp258Currencies::update_balance(Origin::root(), ALICE, SETT, 10).unwrap();
+			assert_eq!(post_info.actual_weight, None);
+		});
+}
+
 #[test]
 fn update_balance_call_fails_if_not_root_origin() {
 	ExtBuilder::default().build().execute_with(|| {
@@ -282,3 +448,1304 @@ fn call_event_should_work() {
 			assert!(System::events().iter().any(|record| record.event == transferred_event));
 		});
 }
+
+#[test]
+fn lock_and_reserve_events_should_work() {
+	ExtBuilder::default()
+		.one_hundred_for_alice_n_bob_n_serper_n_settpay()
+		.build()
+		.execute_with(|| {
+			System::set_block_number(1);
+
+			assert_ok!(Stp258Currencies::set_lock(ID_1, SETT, &ALICE, 50 * 10_000));
+			let lock_set_event = Event::stp258_currencies(crate::Event::LockSet(ID_1, SETT, ALICE, 50 * 10_000));
+			assert!(System::events().iter().any(|record| record.event == lock_set_event));
+
+			assert_ok!(Stp258Currencies::remove_lock(ID_1, DNAR, &ALICE));
+			let lock_removed_event = Event::stp258_currencies(crate::Event::LockRemoved(ID_1, DNAR, ALICE));
+			assert!(System::events().iter().any(|record| record.event == lock_removed_event));
+
+			assert_ok!(Stp258Currencies::reserve(DNAR, &ALICE, 40));
+			let reserved_event = Event::stp258_currencies(crate::Event::Reserved(DNAR, ALICE, 40));
+			assert!(System::events().iter().any(|record| record.event == reserved_event));
+
+			assert_eq!(Stp258Currencies::unreserve(DNAR, &ALICE, 10), 0);
+			let unreserved_event = Event::stp258_currencies(crate::Event::Unreserved(DNAR, ALICE, 10));
+			assert!(System::events().iter().any(|record| record.event == unreserved_event));
+
+			assert_eq!(Stp258Currencies::slash_reserved(DNAR, &ALICE, 10), 0);
+			let slashed_event = Event::stp258_currencies(crate::Event::Slashed(DNAR, ALICE, 0, 10));
+			assert!(System::events().iter().any(|record| record.event == slashed_event));
+
+			assert_eq!(
+				Stp258Currencies::repatriate_reserved(DNAR, &ALICE, &BOB, 30, BalanceStatus::Free),
+				Ok(10)
+			);
+			let repatriated_event = Event::stp258_currencies(crate::Event::ReserveRepatriated(
+				DNAR,
+				ALICE,
+				BOB,
+				20,
+				BalanceStatus::Free,
+			));
+			assert!(System::events().iter().any(|record| record.event == repatriated_event));
+
+			assert_eq!(Stp258Currencies::slash(SETT, &ALICE, 10 * 10_000), 0);
+			let slashed_event = Event::stp258_currencies(crate::Event::Slashed(SETT, ALICE, 10 * 10_000, 0));
+			assert!(System::events().iter().any(|record| record.event == slashed_event));
+		});
+}
+
+#[test]
+fn named_reserves_should_be_kept_apart() {
+	ExtBuilder::default()
+		.one_hundred_for_alice_n_bob_n_serper_n_settpay()
+		.build()
+		.execute_with(|| {
+			assert_ok!(Stp258Currencies::reserve_named(&RID_1, SETT, &ALICE, 30 * 10_000));
+			assert_ok!(Stp258Currencies::reserve_named(&RID_2, SETT, &ALICE, 20 * 10_000));
+			assert_eq!(Stp258Currencies::reserved_balance(SETT, &ALICE), 50 * 10_000);
+			assert_eq!(Stp258Currencies::reserved_balance_named(&RID_1, SETT, &ALICE), 30 * 10_000);
+			assert_eq!(Stp258Currencies::reserved_balance_named(&RID_2, SETT, &ALICE), 20 * 10_000);
+
+			// cannot unreserve more than was reserved under the name
+			assert_eq!(
+				Stp258Currencies::unreserve_named(&RID_2, SETT, &ALICE, 30 * 10_000),
+				10 * 10_000
+			);
+			assert_eq!(Stp258Currencies::reserved_balance_named(&RID_2, SETT, &ALICE), 0);
+			assert_eq!(Stp258Currencies::reserved_balance_named(&RID_1, SETT, &ALICE), 30 * 10_000);
+			assert_eq!(Stp258Currencies::reserved_balance(SETT, &ALICE), 30 * 10_000);
+
+			assert_eq!(Stp258Currencies::slash_reserved_named(&RID_1, SETT, &ALICE, 10 * 10_000), 0);
+			assert_eq!(Stp258Currencies::reserved_balance_named(&RID_1, SETT, &ALICE), 20 * 10_000);
+
+			assert_eq!(Stp258Currencies::unreserve_all_named(&RID_1, SETT, &ALICE), 20 * 10_000);
+			assert_eq!(Stp258Currencies::reserved_balance(SETT, &ALICE), 0);
+			assert_eq!(Stp258Currencies::free_balance(SETT, &ALICE), 90 * 10_000);
+		});
+}
+
+#[test]
+fn named_reserves_repatriate_should_work() {
+	ExtBuilder::default()
+		.one_hundred_for_alice_n_bob_n_serper_n_settpay()
+		.build()
+		.execute_with(|| {
+			assert_ok!(Stp258Native::reserve_named(&RID_1, &ALICE, 50));
+			assert_ok!(Stp258Native::reserve(&ALICE, 10));
+
+			assert_eq!(
+				Stp258Native::repatriate_reserved_named(&RID_1, &ALICE, &BOB, 30, BalanceStatus::Reserved),
+				Ok(0)
+			);
+			assert_eq!(Stp258Native::reserved_balance_named(&RID_1, &ALICE), 20);
+			assert_eq!(Stp258Native::reserved_balance_named(&RID_1, &BOB), 30);
+			assert_eq!(Stp258Native::reserved_balance(&BOB), 30);
+
+			assert_eq!(
+				Stp258Native::repatriate_reserved_named(&RID_1, &ALICE, &BOB, 30, BalanceStatus::Free),
+				Ok(10)
+			);
+			assert_eq!(Stp258Native::reserved_balance_named(&RID_1, &ALICE), 0);
+			assert_eq!(Stp258Native::reserved_balance(&ALICE), 10);
+			assert_eq!(Stp258Native::free_balance(&BOB), 120);
+		});
+}
+
+#[test]
+fn reason_locks_on_native_currency_should_work() {
+	ExtBuilder::default()
+		.one_hundred_for_alice_n_bob_n_serper_n_settpay()
+		.build()
+		.execute_with(|| {
+			assert_ok!(Stp258Currencies::set_lock_with_reasons(
+				ID_1,
+				DNAR,
+				&ALICE,
+				50,
+				WithdrawReasons::TRANSFER
+			));
+			assert_eq!(PalletBalances::locks(&ALICE).len(), 1);
+			assert_ok!(Stp258Currencies::ensure_can_withdraw_for(
+				DNAR,
+				&ALICE,
+				60,
+				WithdrawReasons::TRANSACTION_PAYMENT
+			));
+			assert_noop!(
+				Stp258Currencies::ensure_can_withdraw_for(DNAR, &ALICE, 60, WithdrawReasons::TRANSFER),
+				pallet_balances::Error::<Runtime>::LiquidityRestrictions
+			);
+			assert_noop!(
+				Stp258Currencies::transfer(Some(ALICE).into(), BOB, DNAR, 60),
+				pallet_balances::Error::<Runtime>::LiquidityRestrictions
+			);
+		});
+}
+
+#[test]
+fn reason_locks_on_non_native_currency_should_work() {
+	ExtBuilder::default()
+		.one_hundred_for_alice_n_bob_n_serper_n_settpay()
+		.build()
+		.execute_with(|| {
+			assert_ok!(Stp258Currencies::set_lock_with_reasons(
+				ID_1,
+				SETT,
+				&ALICE,
+				50 * 10_000,
+				WithdrawReasons::TRANSFER
+			));
+			assert_eq!(Stp258Serp::locks(&ALICE, SETT).len(), 0);
+			assert_eq!(
+				Stp258Currencies::reason_locks((ALICE, SETT), ID_1),
+				Some((50 * 10_000, WithdrawReasons::TRANSFER))
+			);
+
+			assert_ok!(Stp258Currencies::ensure_can_withdraw_for(
+				SETT,
+				&ALICE,
+				60 * 10_000,
+				WithdrawReasons::TRANSACTION_PAYMENT
+			));
+			assert_noop!(
+				Stp258Currencies::transfer(Some(ALICE).into(), BOB, SETT, 60 * 10_000),
+				Error::<Runtime>::LiquidityRestrictions
+			);
+			assert_ok!(Stp258Currencies::transfer(Some(ALICE).into(), BOB, SETT, 50 * 10_000));
+
+			// extending with the remaining reasons hands the lock to the backend
+			assert_ok!(Stp258Currencies::extend_lock_with_reasons(
+				ID_1,
+				SETT,
+				&ALICE,
+				10 * 10_000,
+				!WithdrawReasons::TRANSFER
+			));
+			assert_eq!(Stp258Currencies::reason_locks((ALICE, SETT), ID_1), None);
+			assert_eq!(Stp258Serp::locks(&ALICE, SETT).len(), 1);
+
+			assert_ok!(Stp258Currencies::remove_lock(ID_1, SETT, &ALICE));
+			assert_eq!(Stp258Serp::locks(&ALICE, SETT).len(), 0);
+		});
+}
+
+#[test]
+fn set_lock_until_should_expire() {
+	ExtBuilder::default()
+		.one_hundred_for_alice_n_bob_n_serper_n_settpay()
+		.build()
+		.execute_with(|| {
+			System::set_block_number(1);
+
+			assert_noop!(
+				Stp258Currencies::set_lock_until(ID_1, DNAR, &ALICE, 50, 1),
+				Error::<Runtime>::LockExpiryInPast
+			);
+			assert_ok!(Stp258Currencies::set_lock_until(ID_1, DNAR, &ALICE, 50, 5));
+			assert_ok!(Stp258Currencies::set_lock_until(ID_1, SETT, &ALICE, 50 * 10_000, 5));
+			assert_noop!(
+				Stp258Currencies::set_lock_until(ID_2, SETT, &ALICE, 50 * 10_000, 5),
+				Error::<Runtime>::TooManyExpiringLocks
+			);
+			assert_eq!(PalletBalances::locks(&ALICE).len(), 1);
+			assert_eq!(Stp258Serp::locks(&ALICE, SETT).len(), 1);
+
+			// setting the lock again makes it permanent
+			assert_ok!(Stp258Currencies::set_lock(ID_1, SETT, &ALICE, 50 * 10_000));
+			assert_eq!(Stp258Currencies::lock_expiries((ALICE, SETT), ID_1), None);
+
+			Stp258Currencies::on_initialize(4);
+			assert_eq!(PalletBalances::locks(&ALICE).len(), 1);
+
+			Stp258Currencies::on_initialize(5);
+			assert_eq!(PalletBalances::locks(&ALICE).len(), 0);
+			assert_eq!(Stp258Serp::locks(&ALICE, SETT).len(), 1);
+			assert_eq!(Stp258Currencies::expiring_locks(5), vec![]);
+			assert_eq!(Stp258Currencies::lock_expiries((ALICE, DNAR), ID_1), None);
+		});
+}
+
+#[test]
+fn set_lock_until_frees_the_previous_expiry() {
+	ExtBuilder::default()
+		.one_hundred_for_alice_n_bob_n_serper_n_settpay()
+		.build()
+		.execute_with(|| {
+			System::set_block_number(1);
+
+			assert_ok!(Stp258Currencies::set_lock_until(ID_1, DNAR, &ALICE, 50, 5));
+			assert_ok!(Stp258Currencies::set_lock_until(ID_1, SETT, &ALICE, 50 * 10_000, 5));
+
+			// moving an expiry frees its slot
+			assert_ok!(Stp258Currencies::set_lock_until(ID_1, DNAR, &ALICE, 50, 6));
+			assert_eq!(Stp258Currencies::expiring_locks(5), vec![(ALICE, SETT, ID_1)]);
+			assert_eq!(Stp258Currencies::expiring_locks(6), vec![(ALICE, DNAR, ID_1)]);
+			assert_ok!(Stp258Currencies::set_lock_until(ID_2, SETT, &ALICE, 10 * 10_000, 5));
+
+			// and so does removing the lock, down to the whole block
+			assert_ok!(Stp258Currencies::remove_lock(ID_1, DNAR, &ALICE));
+			assert!(!ExpiringLocks::<Runtime>::contains_key(6));
+
+			// a full block leaves the lock and its expiry as they were
+			assert_ok!(Stp258Currencies::set_lock_until(ID_2, DNAR, &ALICE, 20, 7));
+			assert_noop!(
+				Stp258Currencies::set_lock_until(ID_2, DNAR, &ALICE, 30, 5),
+				Error::<Runtime>::TooManyExpiringLocks
+			);
+			assert_eq!(Stp258Currencies::lock_expiries((ALICE, DNAR), ID_2), Some(7));
+
+			Stp258Currencies::on_initialize(5);
+			assert_eq!(Stp258Serp::locks(&ALICE, SETT).len(), 0);
+			assert_eq!(Stp258Currencies::locks(SETT, &ALICE), vec![]);
+		});
+}
+
+#[test]
+fn vested_transfer_should_work() {
+	ExtBuilder::default()
+		.one_hundred_for_alice_n_bob_n_serper_n_settpay()
+		.build()
+		.execute_with(|| {
+			System::set_block_number(1);
+
+			let schedule = VestingSchedule {
+				start: 0,
+				period: 10,
+				period_count: 1,
+				per_period: 100 * 10_000,
+			};
+			assert_ok!(Stp258Currencies::vested_transfer(
+				Some(ALICE).into(),
+				BOB,
+				SETT,
+				schedule.clone()
+			));
+			assert_eq!(Stp258Currencies::vesting_schedules(&BOB, SETT), vec![schedule.clone()]);
+			assert_eq!(Stp258Serp::locks(&BOB, SETT).len(), 1);
+
+			let vested_event = Event::stp258_currencies(crate::Event::VestingScheduleAdded(SETT, ALICE, BOB, schedule));
+			assert!(System::events().iter().any(|record| record.event == vested_event));
+
+			assert_noop!(
+				Stp258Currencies::transfer(Some(BOB).into(), ALICE, SETT, 101 * 10_000),
+				stp258_serp::Error::<Runtime>::LiquidityRestrictions
+			);
+		});
+}
+
+#[test]
+fn vested_transfer_fails_if_invalid_schedule() {
+	ExtBuilder::default()
+		.one_hundred_for_alice_n_bob_n_serper_n_settpay()
+		.build()
+		.execute_with(|| {
+			assert_noop!(
+				Stp258Currencies::vested_transfer(
+					Some(ALICE).into(),
+					BOB,
+					DNAR,
+					VestingSchedule {
+						start: 1,
+						period: 0,
+						period_count: 1,
+						per_period: 10,
+					}
+				),
+				Error::<Runtime>::ZeroVestingPeriod
+			);
+			assert_noop!(
+				Stp258Currencies::vested_transfer(
+					Some(ALICE).into(),
+					BOB,
+					DNAR,
+					VestingSchedule {
+						start: 1,
+						period: 1,
+						period_count: 0,
+						per_period: 10,
+					}
+				),
+				Error::<Runtime>::ZeroVestingPeriodCount
+			);
+		});
+}
+
+#[test]
+fn claim_releases_vested_balance_per_block() {
+	ExtBuilder::default()
+		.one_hundred_for_alice_n_bob_n_serper_n_settpay()
+		.build()
+		.execute_with(|| {
+			System::set_block_number(1);
+
+			let schedule = VestingSchedule {
+				start: 0,
+				period: 1,
+				period_count: 10,
+				per_period: 5,
+			};
+			assert_ok!(Stp258Currencies::vested_transfer(Some(ALICE).into(), BOB, DNAR, schedule));
+			assert_eq!(PalletBalances::locks(&BOB)[0].amount, 50);
+
+			System::set_block_number(4);
+			assert_ok!(Stp258Currencies::claim(Some(BOB).into(), DNAR));
+			assert_eq!(PalletBalances::locks(&BOB)[0].amount, 30);
+			assert_ok!(Stp258Currencies::transfer(Some(BOB).into(), ALICE, DNAR, 120));
+
+			System::set_block_number(10);
+			assert_ok!(Stp258Currencies::claim(Some(BOB).into(), DNAR));
+			assert_eq!(PalletBalances::locks(&BOB).len(), 0);
+			assert_eq!(Stp258Currencies::vesting_schedules(&BOB, DNAR), vec![]);
+		});
+}
+
+#[test]
+fn update_vesting_schedules_should_work() {
+	ExtBuilder::default()
+		.one_hundred_for_alice_n_bob_n_serper_n_settpay()
+		.build()
+		.execute_with(|| {
+			let schedule = VestingSchedule {
+				start: 0,
+				period: 10,
+				period_count: 2,
+				per_period: 10 * 1_000,
+			};
+			assert_noop!(
+				Stp258Currencies::update_vesting_schedules(Some(ALICE).into(), BOB, JUSD, vec![schedule.clone()]),
+				BadOrigin
+			);
+			assert_ok!(Stp258Currencies::update_vesting_schedules(
+				Origin::root(),
+				BOB,
+				JUSD,
+				vec![schedule.clone()]
+			));
+			assert_eq!(Stp258Currencies::vesting_schedules(&BOB, JUSD), vec![schedule]);
+			assert_eq!(Stp258Serp::locks(&BOB, JUSD).len(), 1);
+
+			assert_noop!(
+				Stp258Currencies::update_vesting_schedules(
+					Origin::root(),
+					BOB,
+					JUSD,
+					vec![VestingSchedule {
+						start: 0,
+						period: 10,
+						period_count: 2,
+						per_period: 100 * 1_000,
+					}]
+				),
+				Error::<Runtime>::InsufficientBalanceToLock
+			);
+
+			assert_ok!(Stp258Currencies::update_vesting_schedules(Origin::root(), BOB, JUSD, vec![]));
+			assert_eq!(Stp258Serp::locks(&BOB, JUSD).len(), 0);
+		});
+}
+
+#[test]
+fn register_currency_should_work() {
+	ExtBuilder::default()
+		.registered_currencies(vec![])
+		.build()
+		.execute_with(|| {
+			System::set_block_number(1);
+
+			assert_noop!(
+				Stp258Currencies::register_currency(Some(ALICE).into(), SETT, metadata(b"SETT", 4)),
+				BadOrigin
+			);
+			assert_ok!(Stp258Currencies::register_currency(
+				Origin::root(),
+				SETT,
+				CurrencyMetadata {
+					peg: Some(b"USD".to_vec()),
+					is_native: true,
+					..metadata(b"SETT", 4)
+				}
+			));
+			let registered = CurrencyMetadata {
+				peg: Some(b"USD".to_vec()),
+				..metadata(b"SETT", 4)
+			};
+			assert_eq!(Stp258Currencies::currency_metadata(SETT), Some(registered.clone()));
+			let registered_event = Event::stp258_currencies(crate::Event::CurrencyRegistered(SETT, registered));
+			assert!(System::events().iter().any(|record| record.event == registered_event));
+
+			assert_noop!(
+				Stp258Currencies::register_currency(Origin::root(), SETT, metadata(b"SETT", 4)),
+				Error::<Runtime>::CurrencyAlreadyRegistered
+			);
+			assert_noop!(
+				Stp258Currencies::update_currency(Origin::root(), JUSD, metadata(b"JUSD", 3)),
+				Error::<Runtime>::CurrencyNotRegistered
+			);
+
+			assert_ok!(Stp258Currencies::register_currency(Origin::root(), DNAR, metadata(b"DNAR", 3)));
+			assert_eq!(Stp258Currencies::currency_metadata(DNAR).map(|m| m.is_native), Some(true));
+		});
+}
+
+#[test]
+fn unregistered_currency_is_rejected() {
+	ExtBuilder::default()
+		.one_hundred_for_alice_n_bob_n_serper_n_settpay()
+		.registered_currencies(vec![(DNAR, metadata(b"DNAR", 3))])
+		.build()
+		.execute_with(|| {
+			assert_noop!(
+				Stp258Currencies::transfer(Some(ALICE).into(), BOB, SETT, 10),
+				Error::<Runtime>::CurrencyNotRegistered
+			);
+			assert_noop!(
+				Stp258Currencies::update_balance(Origin::root(), ALICE, JUSD, 10),
+				Error::<Runtime>::CurrencyNotRegistered
+			);
+			assert_ok!(Stp258Currencies::transfer(Some(ALICE).into(), BOB, DNAR, 10));
+		});
+}
+
+#[test]
+fn deprecated_currency_cannot_be_issued() {
+	ExtBuilder::default()
+		.one_hundred_for_alice_n_bob_n_serper_n_settpay()
+		.build()
+		.execute_with(|| {
+			assert_ok!(Stp258Currencies::update_currency(
+				Origin::root(),
+				JUSD,
+				CurrencyMetadata {
+					status: CurrencyStatus::Deprecated,
+					..metadata(b"JUSD", 3)
+				}
+			));
+			assert_noop!(
+				Stp258Currencies::update_balance(Origin::root(), ALICE, JUSD, 10),
+				Error::<Runtime>::CurrencyDeprecated
+			);
+			assert_ok!(Stp258Currencies::update_balance(Origin::root(), ALICE, JUSD, -10));
+			assert_ok!(Stp258Currencies::transfer(Some(ALICE).into(), BOB, JUSD, 10));
+		});
+}
+
+#[test]
+fn paused_currency_cannot_move() {
+	ExtBuilder::default()
+		.one_hundred_for_alice_n_bob_n_serper_n_settpay()
+		.build()
+		.execute_with(|| {
+			System::set_block_number(1);
+
+			assert_noop!(Stp258Currencies::pause_currency(Some(ALICE).into(), JUSD), BadOrigin);
+			assert_ok!(Stp258Currencies::pause_currency(Origin::root(), JUSD));
+			assert!(Stp258Currencies::is_paused(JUSD));
+			let paused_event = Event::stp258_currencies(crate::Event::CurrencyPaused(JUSD));
+			assert!(System::events().iter().any(|record| record.event == paused_event));
+
+			assert_noop!(
+				Stp258Currencies::transfer(Some(ALICE).into(), BOB, JUSD, 10),
+				Error::<Runtime>::CurrencyPaused
+			);
+			assert_noop!(
+				Stp258Currencies::deposit(JUSD, &ALICE, 10),
+				Error::<Runtime>::CurrencyPaused
+			);
+			assert_noop!(
+				Stp258Currencies::withdraw(JUSD, &ALICE, 10),
+				Error::<Runtime>::CurrencyPaused
+			);
+			assert_noop!(
+				Stp258Currencies::update_balance(Origin::root(), ALICE, JUSD, 10),
+				Error::<Runtime>::CurrencyPaused
+			);
+			assert_ok!(Stp258Currencies::transfer(Some(ALICE).into(), BOB, SETT, 10));
+
+			assert_ok!(Stp258Currencies::pause_currency(Origin::root(), DNAR));
+			assert_noop!(
+				Stp258Currencies::transfer_native_currency(Some(ALICE).into(), BOB, 10),
+				Error::<Runtime>::CurrencyPaused
+			);
+
+			assert_ok!(Stp258Currencies::unpause_currency(Origin::root(), JUSD));
+			assert!(!Stp258Currencies::is_paused(JUSD));
+			let unpaused_event = Event::stp258_currencies(crate::Event::CurrencyUnpaused(JUSD));
+			assert!(System::events().iter().any(|record| record.event == unpaused_event));
+			assert_ok!(Stp258Currencies::transfer(Some(ALICE).into(), BOB, JUSD, 10));
+		});
+}
+
+#[test]
+fn frozen_account_cannot_send() {
+	ExtBuilder::default()
+		.one_hundred_for_alice_n_bob_n_serper_n_settpay()
+		.build()
+		.execute_with(|| {
+			System::set_block_number(1);
+
+			assert_ok!(Stp258Currencies::reserve(SETT, &ALICE, 10 * 10_000));
+			assert_noop!(Stp258Currencies::freeze_account(Some(BOB).into(), ALICE, SETT), BadOrigin);
+			assert_ok!(Stp258Currencies::freeze_account(Origin::root(), ALICE, SETT));
+			assert!(Stp258Currencies::is_frozen(&ALICE, SETT));
+			let frozen_event = Event::stp258_currencies(crate::Event::AccountFrozen(SETT, ALICE));
+			assert!(System::events().iter().any(|record| record.event == frozen_event));
+
+			assert_noop!(
+				Stp258Currencies::transfer(Some(ALICE).into(), BOB, SETT, 10),
+				Error::<Runtime>::AccountFrozen
+			);
+			assert_noop!(Stp258Currencies::withdraw(SETT, &ALICE, 10), Error::<Runtime>::AccountFrozen);
+			assert_noop!(Stp258Currencies::reserve(SETT, &ALICE, 10), Error::<Runtime>::AccountFrozen);
+			assert_noop!(
+				Stp258Currencies::repatriate_reserved(SETT, &ALICE, &BOB, 10, BalanceStatus::Free),
+				Error::<Runtime>::AccountFrozen
+			);
+			assert_noop!(Stp258Currencies::merge_account(&ALICE, &BOB), Error::<Runtime>::AccountFrozen);
+			assert_noop!(
+				<Currency<Runtime, GetSett> as Stp258Asset<AccountId>>::transfer(&ALICE, &BOB, 10),
+				Error::<Runtime>::AccountFrozen
+			);
+
+			// incoming funds and other currencies are not affected
+			assert_ok!(Stp258Currencies::transfer(Some(BOB).into(), ALICE, SETT, 10));
+			assert_ok!(Stp258Currencies::transfer(Some(ALICE).into(), BOB, JUSD, 10));
+
+			assert_ok!(Stp258Currencies::thaw_account(Origin::root(), ALICE, SETT));
+			assert!(!Stp258Currencies::is_frozen(&ALICE, SETT));
+			assert_ok!(Stp258Currencies::transfer(Some(ALICE).into(), BOB, SETT, 10));
+		});
+}
+
+#[test]
+fn issuer_roles_should_work() {
+	ExtBuilder::default()
+		.one_hundred_for_alice_n_bob_n_serper_n_settpay()
+		.build()
+		.execute_with(|| {
+			System::set_block_number(1);
+
+			assert_noop!(
+				Stp258Currencies::mint(Some(ALICE).into(), JUSD, BOB, 10),
+				Error::<Runtime>::RolesNotSet
+			);
+			assert_noop!(
+				Stp258Currencies::set_roles(Some(ALICE).into(), JUSD, ALICE, ALICE, ALICE),
+				BadOrigin
+			);
+			assert_ok!(Stp258Currencies::set_roles(Origin::root(), JUSD, ALICE, SERPER, SETTPAY));
+			let roles_event = Event::stp258_currencies(crate::Event::RolesSet(JUSD, ALICE, SERPER, SETTPAY));
+			assert!(System::events().iter().any(|record| record.event == roles_event));
+
+			assert_noop!(
+				Stp258Currencies::mint(Some(BOB).into(), JUSD, BOB, 10),
+				Error::<Runtime>::NoPermission
+			);
+			assert_ok!(Stp258Currencies::mint(Some(ALICE).into(), JUSD, BOB, 10));
+			assert_eq!(Stp258Currencies::free_balance(JUSD, &BOB), 100 * 1_000 + 10);
+			let deposited_event = Event::stp258_currencies(crate::Event::Deposited(JUSD, BOB, 10));
+			assert!(System::events().iter().any(|record| record.event == deposited_event));
+
+			assert_ok!(Stp258Currencies::burn(Some(ALICE).into(), JUSD, BOB, 20));
+			assert_eq!(Stp258Currencies::free_balance(JUSD, &BOB), 100 * 1_000 - 10);
+			assert_eq!(Stp258Currencies::total_issuance(JUSD), 400 * 1_000 - 10);
+
+			// the freezer may freeze without `FreezeOrigin`
+			assert_noop!(
+				Stp258Currencies::freeze_account(Some(ALICE).into(), BOB, JUSD),
+				Error::<Runtime>::NoPermission
+			);
+			assert_ok!(Stp258Currencies::freeze_account(Some(SERPER).into(), BOB, JUSD));
+			assert!(Stp258Currencies::is_frozen(&BOB, JUSD));
+
+			assert_noop!(
+				Stp258Currencies::transfer_roles(Some(ALICE).into(), JUSD, BOB, BOB, BOB),
+				Error::<Runtime>::NoPermission
+			);
+			assert_ok!(Stp258Currencies::transfer_roles(Some(SETTPAY).into(), JUSD, BOB, BOB, BOB));
+			assert_noop!(
+				Stp258Currencies::mint(Some(ALICE).into(), JUSD, BOB, 10),
+				Error::<Runtime>::NoPermission
+			);
+			assert_ok!(Stp258Currencies::mint(Some(BOB).into(), JUSD, BOB, 10));
+		});
+}
+
+#[test]
+fn supply_cap_limits_issuance() {
+	ExtBuilder::default()
+		.one_hundred_for_alice_n_bob_n_serper_n_settpay()
+		.build()
+		.execute_with(|| {
+			System::set_block_number(1);
+
+			assert_eq!(Stp258Currencies::supply_headroom(JUSD), None);
+			assert_noop!(
+				Stp258Currencies::set_max_supply(Some(ALICE).into(), JUSD, Some(400_100)),
+				BadOrigin
+			);
+			assert_ok!(Stp258Currencies::set_max_supply(Origin::root(), JUSD, Some(400_100)));
+			assert_eq!(Stp258Currencies::max_supply(JUSD), Some(400_100));
+			assert_eq!(Stp258Currencies::supply_headroom(JUSD), Some(100));
+			let cap_event = Event::stp258_currencies(crate::Event::MaxSupplySet(JUSD, Some(400_100)));
+			assert!(System::events().iter().any(|record| record.event == cap_event));
+
+			assert_ok!(Stp258Currencies::deposit(JUSD, &ALICE, 60));
+			assert_eq!(Stp258Currencies::supply_headroom(JUSD), Some(40));
+			assert_noop!(
+				Stp258Currencies::deposit(JUSD, &ALICE, 41),
+				Error::<Runtime>::SupplyCapExceeded
+			);
+			assert_noop!(
+				Stp258Currencies::update_balance(Origin::root(), ALICE, JUSD, 41),
+				Error::<Runtime>::SupplyCapExceeded
+			);
+			assert_ok!(Stp258Currencies::update_balance(Origin::root(), ALICE, JUSD, -60));
+			assert_ok!(Stp258Currencies::update_balance(Origin::root(), ALICE, JUSD, 100));
+			assert_eq!(Stp258Currencies::supply_headroom(JUSD), Some(0));
+
+			// other currencies stay uncapped
+			assert_ok!(Stp258Currencies::deposit(SETT, &ALICE, 1_000));
+
+			assert_ok!(Stp258Currencies::set_max_supply(Origin::root(), JUSD, None));
+			assert_eq!(Stp258Currencies::supply_headroom(JUSD), None);
+			assert_ok!(Stp258Currencies::deposit(JUSD, &ALICE, 41));
+		});
+}
+
+#[test]
+fn issuance_limits_roll_over_the_window() {
+	ExtBuilder::default()
+		.one_hundred_for_alice_n_bob_n_serper_n_settpay()
+		.build()
+		.execute_with(|| {
+			System::set_block_number(1);
+
+			let limits = IssuanceLimits { mint: 100, burn: 50 };
+			assert_noop!(
+				Stp258Currencies::set_issuance_limits(Some(ALICE).into(), JUSD, Some(limits.clone())),
+				BadOrigin
+			);
+			assert_ok!(Stp258Currencies::set_issuance_limits(Origin::root(), JUSD, Some(limits.clone())));
+			assert_eq!(Stp258Currencies::issuance_limits(JUSD), Some(limits.clone()));
+			let limits_event = Event::stp258_currencies(crate::Event::IssuanceLimitsSet(JUSD, Some(limits)));
+			assert!(System::events().iter().any(|record| record.event == limits_event));
+
+			assert_ok!(Stp258Currencies::deposit(JUSD, &ALICE, 60));
+			assert_ok!(Stp258Currencies::update_balance(Origin::root(), ALICE, JUSD, 40));
+			assert_eq!(Stp258Currencies::issued_in_window(JUSD), (100, 0));
+			assert_noop!(
+				Stp258Currencies::deposit(JUSD, &ALICE, 1),
+				Error::<Runtime>::MintLimitExceeded
+			);
+			assert_noop!(
+				Stp258Currencies::update_balance(Origin::root(), ALICE, JUSD, 1),
+				Error::<Runtime>::MintLimitExceeded
+			);
+
+			assert_ok!(Stp258Currencies::withdraw(JUSD, &ALICE, 30));
+			assert_ok!(Stp258Currencies::update_balance(Origin::root(), ALICE, JUSD, -20));
+			assert_eq!(Stp258Currencies::issued_in_window(JUSD), (100, 50));
+			assert_noop!(
+				Stp258Currencies::withdraw(JUSD, &ALICE, 1),
+				Error::<Runtime>::BurnLimitExceeded
+			);
+			assert_noop!(
+				Stp258Currencies::update_balance(Origin::root(), ALICE, JUSD, -1),
+				Error::<Runtime>::BurnLimitExceeded
+			);
+
+			// unlimited currencies are neither limited nor tracked
+			assert_ok!(Stp258Currencies::deposit(SETT, &ALICE, 1_000));
+			assert_eq!(Stp258Currencies::window_issuance(SETT), vec![]);
+
+			// issuance counts for the ten blocks from the one it happened in
+			System::set_block_number(10);
+			assert_eq!(Stp258Currencies::issued_in_window(JUSD), (100, 50));
+			assert_noop!(
+				Stp258Currencies::deposit(JUSD, &ALICE, 1),
+				Error::<Runtime>::MintLimitExceeded
+			);
+			System::set_block_number(11);
+			assert_eq!(Stp258Currencies::issued_in_window(JUSD), (0, 0));
+			assert_ok!(Stp258Currencies::deposit(JUSD, &ALICE, 60));
+			assert_eq!(Stp258Currencies::window_issuance(JUSD), vec![(11, 60, 0)]);
+
+			// no two limits fit in any window, across a boundary or not
+			System::set_block_number(15);
+			assert_ok!(Stp258Currencies::deposit(JUSD, &ALICE, 40));
+			assert_ok!(Stp258Currencies::withdraw(JUSD, &ALICE, 50));
+			System::set_block_number(20);
+			assert_noop!(
+				Stp258Currencies::deposit(JUSD, &ALICE, 1),
+				Error::<Runtime>::MintLimitExceeded
+			);
+			System::set_block_number(21);
+			assert_eq!(Stp258Currencies::issued_in_window(JUSD), (40, 50));
+			assert_noop!(
+				Stp258Currencies::deposit(JUSD, &ALICE, 61),
+				Error::<Runtime>::MintLimitExceeded
+			);
+			assert_noop!(
+				Stp258Currencies::withdraw(JUSD, &ALICE, 1),
+				Error::<Runtime>::BurnLimitExceeded
+			);
+			assert_ok!(Stp258Currencies::deposit(JUSD, &ALICE, 60));
+			assert_eq!(Stp258Currencies::window_issuance(JUSD), vec![(15, 40, 50), (21, 60, 0)]);
+			System::set_block_number(25);
+			assert_ok!(Stp258Currencies::withdraw(JUSD, &ALICE, 50));
+		});
+}
+
+#[test]
+fn transfer_hooks_observe_and_veto_movements() {
+	ExtBuilder::default()
+		.one_hundred_for_alice_n_bob_n_serper_n_settpay()
+		.build()
+		.execute_with(|| {
+			OBSERVED_MOVEMENTS.with(|v| v.borrow_mut().clear());
+
+			assert_ok!(Stp258Currencies::transfer(Some(ALICE).into(), BOB, SETT, 10));
+			assert_ok!(Stp258Currencies::transfer_native_currency(Some(ALICE).into(), BOB, 5));
+			assert_ok!(Stp258Currencies::deposit(JUSD, &ALICE, 20));
+			assert_ok!(Stp258Currencies::withdraw(DNAR, &ALICE, 3));
+			assert_ok!(Stp258Currencies::update_balance(Origin::root(), BOB, JUSD, 7));
+			assert_ok!(Stp258Currencies::update_balance(Origin::root(), BOB, JUSD, -2));
+			assert_eq!(
+				OBSERVED_MOVEMENTS.with(|v| v.borrow().clone()),
+				vec![
+					("transfer", SETT, ALICE, 10),
+					("transfer", DNAR, ALICE, 5),
+					("deposit", JUSD, ALICE, 20),
+					("withdraw", DNAR, ALICE, 3),
+					("deposit", JUSD, BOB, 7),
+					("withdraw", JUSD, BOB, 2),
+				]
+			);
+
+			VETOED_SENDER.with(|v| *v.borrow_mut() = Some(ALICE));
+			assert_noop!(
+				Stp258Currencies::transfer(Some(ALICE).into(), BOB, SETT, 10),
+				DispatchError::Other("transfer vetoed")
+			);
+			assert_noop!(
+				Stp258Currencies::transfer_native_currency(Some(ALICE).into(), BOB, 5),
+				DispatchError::Other("transfer vetoed")
+			);
+			assert_ok!(Stp258Currencies::transfer(Some(BOB).into(), ALICE, SETT, 10));
+			VETOED_SENDER.with(|v| *v.borrow_mut() = None);
+		});
+}
+
+#[test]
+fn allowances_work() {
+	ExtBuilder::default()
+		.one_hundred_for_alice_n_bob_n_serper_n_settpay()
+		.build()
+		.execute_with(|| {
+			System::set_block_number(1);
+
+			assert_ok!(Stp258Currencies::approve(Some(ALICE).into(), BOB, SETT, 100));
+			assert_eq!(Stp258Currencies::allowance((SETT, &ALICE), &BOB), 100);
+			let approval_event = Event::stp258_currencies(crate::Event::Approval(SETT, ALICE, BOB, 100));
+			assert!(System::events().iter().any(|record| record.event == approval_event));
+
+			assert_ok!(Stp258Currencies::increase_allowance(Some(ALICE).into(), BOB, SETT, 50));
+			assert_eq!(Stp258Currencies::allowance((SETT, &ALICE), &BOB), 150);
+			assert_noop!(
+				Stp258Currencies::decrease_allowance(Some(ALICE).into(), BOB, SETT, 151),
+				Error::<Runtime>::InsufficientAllowance
+			);
+			assert_ok!(Stp258Currencies::decrease_allowance(Some(ALICE).into(), BOB, SETT, 30));
+			assert_eq!(Stp258Currencies::allowance((SETT, &ALICE), &BOB), 120);
+
+			// allowances are per currency
+			assert_eq!(Stp258Currencies::allowance((JUSD, &ALICE), &BOB), 0);
+			assert_noop!(
+				Stp258Currencies::transfer_from(Some(BOB).into(), ALICE, SERPER, JUSD, 1),
+				Error::<Runtime>::InsufficientAllowance
+			);
+
+			assert_ok!(Stp258Currencies::transfer_from(Some(BOB).into(), ALICE, SERPER, SETT, 100));
+			assert_eq!(Stp258Currencies::free_balance(SETT, &ALICE), 100 * 10_000 - 100);
+			assert_eq!(Stp258Currencies::free_balance(SETT, &SERPER), 100 * 10_000 + 100);
+			assert_eq!(Stp258Currencies::allowance((SETT, &ALICE), &BOB), 20);
+			let transferred_event = Event::stp258_currencies(crate::Event::Transferred(SETT, ALICE, SERPER, 100));
+			assert!(System::events().iter().any(|record| record.event == transferred_event));
+
+			assert_noop!(
+				Stp258Currencies::transfer_from(Some(BOB).into(), ALICE, SERPER, SETT, 21),
+				Error::<Runtime>::InsufficientAllowance
+			);
+
+			// the allowance is only consumed by successful transfers
+			assert_ok!(Stp258Currencies::approve(Some(ALICE).into(), BOB, DNAR, 1_000));
+			assert!(Stp258Currencies::transfer_from(Some(BOB).into(), ALICE, SERPER, DNAR, 1_000).is_err());
+			assert_eq!(Stp258Currencies::allowance((DNAR, &ALICE), &BOB), 1_000);
+			assert_ok!(Stp258Currencies::transfer_from(Some(BOB).into(), ALICE, SERPER, DNAR, 10));
+			assert_eq!(Stp258Currencies::free_balance(DNAR, &SERPER), 110);
+			assert_eq!(Stp258Currencies::allowance((DNAR, &ALICE), &BOB), 990);
+		});
+}
+
+#[test]
+fn transfer_batch_is_atomic() {
+	ExtBuilder::default()
+		.one_hundred_for_alice_n_bob_n_serper_n_settpay()
+		.build()
+		.execute_with(|| {
+			let batch_call = mock::Call::Stp258Currencies(crate::Call::transfer_batch(vec![
+				(BOB, SETT, 10),
+				(SERPER, DNAR, 10),
+				(BOB, JUSD, 10),
+			]));
+			assert_eq!(batch_call.get_dispatch_info().weight, <() as WeightInfo>::transfer_batch(3));
+
+			assert_ok!(Stp258Currencies::transfer_batch(
+				Some(ALICE).into(),
+				vec![(BOB, SETT, 10), (SERPER, DNAR, 10), (BOB, JUSD, 10)]
+			));
+			assert_eq!(Stp258Currencies::free_balance(SETT, &BOB), 100 * 10_000 + 10);
+			assert_eq!(Stp258Currencies::free_balance(DNAR, &SERPER), 110);
+			assert_eq!(Stp258Currencies::free_balance(JUSD, &BOB), 100 * 1_000 + 10);
+			assert_eq!(Stp258Currencies::free_balance(DNAR, &ALICE), 90);
+
+			// the last leg fails, so the first one is rolled back
+			assert_noop!(
+				Stp258Currencies::transfer_batch(Some(ALICE).into(), vec![(BOB, SETT, 10), (BOB, DNAR, 1_000)]),
+				pallet_balances::Error::<Runtime>::InsufficientBalance
+			);
+			assert_noop!(
+				Stp258Currencies::transfer_batch(
+					Some(ALICE).into(),
+					vec![(BOB, SETT, 1), (BOB, SETT, 1), (BOB, SETT, 1), (BOB, SETT, 1)]
+				),
+				Error::<Runtime>::TooManyBatchTransfers
+			);
+		});
+}
+
+#[test]
+fn merge_account_respects_and_clears_locks() {
+	ExtBuilder::default()
+		.one_hundred_for_alice_n_bob_n_serper_n_settpay()
+		.build()
+		.execute_with(|| {
+			assert_ok!(Stp258Currencies::set_lock_with_reasons(
+				ID_1,
+				SETT,
+				&ALICE,
+				10,
+				WithdrawReasons::TRANSFER
+			));
+			assert_noop!(
+				Stp258Currencies::merge_account(&ALICE, &BOB),
+				Error::<Runtime>::LiquidityRestrictions
+			);
+
+			// locks that do not hold transfers back are dropped
+			assert_ok!(Stp258Currencies::set_lock_with_reasons(
+				ID_1,
+				SETT,
+				&ALICE,
+				10,
+				WithdrawReasons::RESERVE
+			));
+			assert_ok!(Stp258Currencies::set_lock_with_reasons(
+				ID_2,
+				DNAR,
+				&ALICE,
+				10,
+				WithdrawReasons::TRANSACTION_PAYMENT
+			));
+			assert_ok!(Stp258Currencies::merge_account(&ALICE, &BOB));
+			assert_eq!(Stp258Currencies::free_balance(SETT, &BOB), 200 * 10_000);
+			assert_eq!(Stp258Currencies::free_balance(DNAR, &BOB), 200);
+			assert_eq!(Stp258Currencies::locks(SETT, &ALICE), vec![]);
+			assert_eq!(Stp258Currencies::reason_locks((ALICE, SETT), ID_1), None);
+			assert_eq!(Stp258Currencies::locks(DNAR, &ALICE), vec![]);
+			assert_eq!(PalletBalances::locks(&ALICE).len(), 0);
+		});
+}
+
+#[test]
+fn fees_are_charged_in_the_chosen_currency() {
+	ExtBuilder::default()
+		.one_hundred_for_alice_n_bob_n_serper_n_settpay()
+		.build()
+		.execute_with(|| {
+			System::set_block_number(1);
+			type FeeAdapter = ChargeStp258Fee<Runtime, FeeReceiver>;
+			let call = mock::Call::Stp258Currencies(crate::Call::transfer(BOB, SETT, 10));
+			let info = call.get_dispatch_info();
+
+			// native by default
+			let withdrawn = FeeAdapter::withdraw_fee(&ALICE, &call, &info, 10, 0).unwrap();
+			assert_eq!(withdrawn, Some((DNAR, 10)));
+			assert_eq!(Stp258Currencies::free_balance(DNAR, &ALICE), 90);
+			assert_ok!(FeeAdapter::correct_and_deposit_fee(&ALICE, &info, &Default::default(), 6, 0, withdrawn));
+			assert_eq!(Stp258Currencies::free_balance(DNAR, &ALICE), 94);
+			assert_eq!(Stp258Currencies::free_balance(DNAR, &FeeReceiver::get()), 6);
+			assert_eq!(Stp258Currencies::total_issuance(DNAR), 400);
+			let native_fee_event = Event::stp258_currencies(crate::Event::FeePaid(DNAR, ALICE, 6));
+			assert!(System::events().iter().any(|record| record.event == native_fee_event));
+
+			assert_noop!(
+				Stp258Currencies::set_fee_currency(Some(ALICE).into(), Some(4)),
+				Error::<Runtime>::CurrencyNotRegistered
+			);
+			assert_ok!(Stp258Currencies::set_fee_currency(Some(ALICE).into(), Some(JUSD)));
+			assert_eq!(Stp258Currencies::fee_currency(&ALICE), Some(JUSD));
+			let fee_currency_event = Event::stp258_currencies(crate::Event::FeeCurrencySet(ALICE, Some(JUSD)));
+			assert!(System::events().iter().any(|record| record.event == fee_currency_event));
+
+			let withdrawn = FeeAdapter::withdraw_fee(&ALICE, &call, &info, 10, 0).unwrap();
+			assert_eq!(withdrawn, Some((JUSD, 20)));
+			assert_eq!(Stp258Currencies::free_balance(JUSD, &ALICE), 100 * 1_000 - 20);
+			assert_ok!(FeeAdapter::correct_and_deposit_fee(&ALICE, &info, &Default::default(), 6, 0, withdrawn));
+			assert_eq!(Stp258Currencies::free_balance(JUSD, &ALICE), 100 * 1_000 - 12);
+			assert_eq!(Stp258Currencies::free_balance(JUSD, &FeeReceiver::get()), 12);
+			assert_eq!(Stp258Currencies::total_issuance(JUSD), 4 * 100 * 1_000);
+			assert_eq!(Stp258Currencies::free_balance(DNAR, &ALICE), 94);
+			let fee_event = Event::stp258_currencies(crate::Event::FeePaid(JUSD, ALICE, 12));
+			assert!(System::events().iter().any(|record| record.event == fee_event));
+
+			// fees follow the price of the fee currency and its decimals
+			assert_ok!(Stp258Currencies::set_fee_currency(Some(ALICE).into(), Some(SETT)));
+			let withdrawn = FeeAdapter::withdraw_fee(&ALICE, &call, &info, 10, 0).unwrap();
+			assert_eq!(withdrawn, Some((SETT, 200)));
+			assert_eq!(Stp258Currencies::free_balance(SETT, &ALICE), 100 * 10_000 - 200);
+		});
+}
+
+#[test]
+fn transfer_converted_swaps_through_liquidity_account() {
+	ExtBuilder::default()
+		.one_hundred_for_alice_n_bob_n_serper_n_settpay()
+		.build()
+		.execute_with(|| {
+			System::set_block_number(1);
+
+			// one SETT is one JUSD, one DNAR is two JUSD
+			assert_eq!(Stp258Currencies::convert(SETT, JUSD, 10_000), Ok(1_000));
+			assert_eq!(Stp258Currencies::convert(JUSD, SETT, 1_000), Ok(10_000));
+			assert_eq!(Stp258Currencies::convert(DNAR, SETT, 1_000), Ok(20_000));
+			assert_eq!(Stp258Currencies::convert(SETT, SETT, 7), Ok(7));
+			assert_eq!(
+				Stp258Currencies::convert(SETT, 4, 10_000),
+				Err(Error::<Runtime>::PriceUnavailable)
+			);
+			assert_eq!(
+				Stp258Currencies::convert(DNAR, SETT, u64::MAX / 2),
+				Err(Error::<Runtime>::ConversionOverflow)
+			);
+
+			let liquidity = LiquidityAccount::get();
+			assert_ok!(Stp258Currencies::deposit(JUSD, &liquidity, 5_000));
+
+			assert_noop!(
+				Stp258Currencies::transfer_converted(Some(ALICE).into(), BOB, SETT, JUSD, 20_000, 2_001),
+				Error::<Runtime>::ReceivedTooLittle
+			);
+			assert_ok!(Stp258Currencies::transfer_converted(Some(ALICE).into(), BOB, SETT, JUSD, 20_000, 2_000));
+			assert_eq!(Stp258Currencies::free_balance(SETT, &ALICE), 100 * 10_000 - 20_000);
+			assert_eq!(Stp258Currencies::free_balance(JUSD, &BOB), 100 * 1_000 + 2_000);
+			assert_eq!(Stp258Currencies::free_balance(SETT, &liquidity), 20_000);
+			assert_eq!(Stp258Currencies::free_balance(JUSD, &liquidity), 3_000);
+			let converted_event = Event::stp258_currencies(crate::Event::TransferredConverted(
+				SETT, JUSD, ALICE, BOB, 20_000, 2_000,
+			));
+			assert!(System::events().iter().any(|record| record.event == converted_event));
+
+			// the liquidity account cannot pay out, so the sender keeps its funds
+			assert!(Stp258Currencies::transfer_converted(Some(ALICE).into(), BOB, SETT, JUSD, 40_000, 0).is_err());
+			assert_eq!(Stp258Currencies::free_balance(SETT, &ALICE), 100 * 10_000 - 20_000);
+			assert_eq!(Stp258Currencies::free_balance(SETT, &liquidity), 20_000);
+		});
+}
+
+#[test]
+fn convert_amount_handles_large_amounts() {
+	let dollar: u128 = 1_000_000_000_000_000_000;
+	let price = Price::saturating_from_rational(2, 1);
+
+	// ten million 18 decimals tokens, which overflowed once scaled by a base unit
+	assert_eq!(
+		Stp258Currencies::convert_amount(price, 10_000_000 * dollar, dollar, dollar),
+		Some(20_000_000 * dollar)
+	);
+	assert_eq!(
+		Stp258Currencies::convert_amount(price, 10_000_000 * dollar, dollar, 1_000_000),
+		Some(20_000_000 * 1_000_000)
+	);
+	assert_eq!(
+		Stp258Currencies::convert_amount(price, 10_000_000 * 1_000_000, 1_000_000, dollar),
+		Some(20_000_000 * dollar)
+	);
+	assert_eq!(Stp258Currencies::convert_amount(price, u128::MAX, dollar, dollar), None);
+}
+
+#[test]
+fn convert_amount_up_rounds_up() {
+	let dollar: u128 = 1_000_000_000_000_000_000;
+	let price = Price::saturating_from_rational(2, 1);
+
+	// to fewer decimals
+	assert_eq!(Stp258Currencies::convert_amount(price, 1, dollar, 1_000_000), Some(0));
+	assert_eq!(Stp258Currencies::convert_amount_up(price, 1, dollar, 1_000_000), Some(1));
+	assert_eq!(
+		Stp258Currencies::convert_amount_up(price, 1_500_000_000_000, dollar, 1_000_000),
+		Some(3)
+	);
+	assert_eq!(
+		Stp258Currencies::convert_amount_up(price, 1_500_000_000_001, dollar, 1_000_000),
+		Some(4)
+	);
+
+	// exact conversions are left alone, large ones included
+	assert_eq!(
+		Stp258Currencies::convert_amount_up(price, 10_000_000 * dollar, dollar, 1_000_000),
+		Some(20_000_000 * 1_000_000)
+	);
+	assert_eq!(
+		Stp258Currencies::convert_amount_up(Price::saturating_from_rational(1, 2), 3 * dollar, dollar, dollar),
+		Some(3 * dollar / 2)
+	);
+	assert_eq!(Stp258Currencies::convert_amount_up(price, u128::MAX, dollar, dollar), None);
+}
+
+#[test]
+fn locks_and_all_balances_are_queryable() {
+	ExtBuilder::default()
+		.one_hundred_for_alice_n_bob_n_serper_n_settpay()
+		.build()
+		.execute_with(|| {
+			let mut balances = Stp258Currencies::all_balances(&ALICE);
+			balances.sort();
+			assert_eq!(balances, vec![(DNAR, 100), (SETT, 100 * 10_000), (JUSD, 100 * 1_000)]);
+			assert_ok!(Stp258Currencies::reserve(JUSD, &ALICE, 100 * 1_000));
+			assert_ok!(Stp258Currencies::transfer(Some(ALICE).into(), BOB, SETT, 100 * 10_000));
+			let mut balances = Stp258Currencies::all_balances(&ALICE);
+			balances.sort();
+			assert_eq!(balances, vec![(DNAR, 100), (JUSD, 100 * 1_000)]);
+
+			assert_ok!(Stp258Currencies::set_lock(ID_1, DNAR, &ALICE, 50));
+			assert_ok!(Stp258Currencies::set_lock_with_reasons(
+				ID_1,
+				SETT,
+				&BOB,
+				20,
+				WithdrawReasons::TRANSFER
+			));
+			assert_ok!(Stp258Currencies::extend_lock(ID_2, SETT, &BOB, 30));
+			assert_ok!(Stp258Currencies::extend_lock(ID_2, SETT, &BOB, 10));
+			assert_eq!(Stp258Currencies::locks(DNAR, &ALICE), vec![(ID_1, 50)]);
+			let mut locks = Stp258Currencies::locks(SETT, &BOB);
+			locks.sort();
+			assert_eq!(locks, vec![(ID_1, 20), (ID_2, 30)]);
+
+			assert_ok!(Stp258Currencies::remove_lock(ID_1, SETT, &BOB));
+			assert_eq!(Stp258Currencies::locks(SETT, &BOB), vec![(ID_2, 30)]);
+			assert_eq!(Stp258Currencies::locks(DNAR, &BOB), vec![]);
+		});
+}
+
+#[test]
+fn transferable_balance_excludes_transfer_locks() {
+	ExtBuilder::default()
+		.one_hundred_for_alice_n_bob_n_serper_n_settpay()
+		.build()
+		.execute_with(|| {
+			assert_eq!(Stp258Currencies::transferable_balance(SETT, &ALICE), 100 * 10_000);
+
+			assert_ok!(Stp258Currencies::set_lock_with_reasons(
+				ID_1,
+				SETT,
+				&ALICE,
+				500,
+				WithdrawReasons::RESERVE
+			));
+			assert_eq!(Stp258Currencies::transferable_balance(SETT, &ALICE), 100 * 10_000);
+			assert_ok!(Stp258Currencies::set_lock_with_reasons(
+				ID_2,
+				SETT,
+				&ALICE,
+				300,
+				WithdrawReasons::TRANSFER
+			));
+			assert_eq!(Stp258Currencies::transferable_balance(SETT, &ALICE), 100 * 10_000 - 300);
+			assert_ok!(Stp258Currencies::set_lock(ID_2, SETT, &ALICE, 700));
+			assert_eq!(Stp258Currencies::transferable_balance(SETT, &ALICE), 100 * 10_000 - 700);
+
+			// locks set on the backend directly count too
+			assert_ok!(Stp258Serp::set_lock(*b"backend ", SETT, &ALICE, 900));
+			assert_eq!(Stp258Currencies::transferable_balance(SETT, &ALICE), 100 * 10_000 - 900);
+
+			assert_ok!(Stp258Currencies::set_lock(ID_1, DNAR, &ALICE, 40));
+			assert_eq!(Stp258Currencies::transferable_balance(DNAR, &ALICE), 60);
+			// native locks on fees only do not hold transfers back
+			assert_ok!(Stp258Currencies::set_lock_with_reasons(
+				ID_2,
+				DNAR,
+				&ALICE,
+				80,
+				WithdrawReasons::TRANSACTION_PAYMENT
+			));
+			assert_eq!(Stp258Currencies::transferable_balance(DNAR, &ALICE), 60);
+		});
+}
+
+#[test]
+fn genesis_config_routes_balances_reserves_and_locks() {
+	let mut t = frame_system::GenesisConfig::default()
+		.build_storage::<Runtime>()
+		.unwrap();
+	crate::GenesisConfig::<Runtime> {
+		currencies: vec![(DNAR, metadata(b"DNAR", 3)), (SETT, metadata(b"SETT", 4))],
+		balances: vec![(ALICE, DNAR, 100), (ALICE, SETT, 1_000), (BOB, SETT, 500)],
+		reserves: vec![(ALICE, SETT, 300)],
+		locks: vec![(ALICE, DNAR, ID_1, 40)],
+	}
+	.assimilate_storage(&mut t)
+	.unwrap();
+
+	sp_io::TestExternalities::from(t).execute_with(|| {
+		assert!(Stp258Currencies::currency_metadata(DNAR).unwrap().is_native);
+		assert!(!Stp258Currencies::currency_metadata(SETT).unwrap().is_native);
+		assert_eq!(Stp258Currencies::currency_metadata(JUSD), None);
+
+		assert_eq!(Stp258Currencies::free_balance(DNAR, &ALICE), 100);
+		assert_eq!(Stp258Currencies::free_balance(SETT, &ALICE), 700);
+		assert_eq!(Stp258Currencies::reserved_balance(SETT, &ALICE), 300);
+		assert_eq!(Stp258Currencies::total_issuance(SETT), 1_500);
+		assert_eq!(Stp258Currencies::total_issuance(DNAR), 100);
+		assert_eq!(PalletBalances::locks(&ALICE).len(), 1);
+		assert_eq!(Stp258Currencies::locks(DNAR, &ALICE), vec![(ID_1, 40)]);
+	});
+}
+
+#[test]
+#[should_panic(expected = "duplicate balances in genesis.")]
+fn genesis_config_rejects_duplicate_balances() {
+	let mut t = frame_system::GenesisConfig::default()
+		.build_storage::<Runtime>()
+		.unwrap();
+	crate::GenesisConfig::<Runtime> {
+		currencies: vec![(SETT, metadata(b"SETT", 4))],
+		balances: vec![(ALICE, SETT, 1_000), (ALICE, SETT, 500)],
+		..Default::default()
+	}
+	.assimilate_storage(&mut t)
+	.unwrap();
+}
+
+#[test]
+#[should_panic(expected = "the balance of any account should always be at least the existential deposit.")]
+fn genesis_config_rejects_native_balances_below_existential_deposit() {
+	let mut t = frame_system::GenesisConfig::default()
+		.build_storage::<Runtime>()
+		.unwrap();
+	crate::GenesisConfig::<Runtime> {
+		currencies: vec![(DNAR, metadata(b"DNAR", 3))],
+		balances: vec![(ALICE, DNAR, 0)],
+		..Default::default()
+	}
+	.assimilate_storage(&mut t)
+	.unwrap();
+}
+
+#[test]
+fn fees_only_honour_transaction_payment_locks() {
+	ExtBuilder::default()
+		.one_hundred_for_alice_n_bob_n_serper_n_settpay()
+		.build()
+		.execute_with(|| {
+			type FeeAdapter = ChargeStp258Fee<Runtime, FeeReceiver>;
+			let call = mock::Call::Stp258Currencies(crate::Call::transfer(BOB, SETT, 10));
+			let info = call.get_dispatch_info();
+
+			// a transfer-only lock leaves the fee payable
+			assert_ok!(Stp258Currencies::set_lock_with_reasons(
+				ID_1,
+				DNAR,
+				&ALICE,
+				100,
+				WithdrawReasons::TRANSFER
+			));
+			assert_eq!(FeeAdapter::withdraw_fee(&ALICE, &call, &info, 10, 0), Ok(Some((DNAR, 10))));
+			assert_ok!(Stp258Currencies::set_lock_with_reasons(
+				ID_1,
+				JUSD,
+				&BOB,
+				100 * 1_000,
+				WithdrawReasons::TRANSFER
+			));
+			assert_ok!(Stp258Currencies::set_fee_currency(Some(BOB).into(), Some(JUSD)));
+			assert_eq!(FeeAdapter::withdraw_fee(&BOB, &call, &info, 10, 0), Ok(Some((JUSD, 20))));
+
+			// a lock on transaction payments does not
+			assert_ok!(Stp258Currencies::set_lock_with_reasons(
+				ID_2,
+				DNAR,
+				&ALICE,
+				90,
+				WithdrawReasons::TRANSACTION_PAYMENT
+			));
+			assert!(FeeAdapter::withdraw_fee(&ALICE, &call, &info, 10, 0).is_err());
+			// and a lock on tips only matters with a tip
+			assert_ok!(Stp258Currencies::set_lock_with_reasons(ID_2, DNAR, &ALICE, 85, WithdrawReasons::TIP));
+			assert_eq!(FeeAdapter::withdraw_fee(&ALICE, &call, &info, 5, 0), Ok(Some((DNAR, 5))));
+			assert!(FeeAdapter::withdraw_fee(&ALICE, &call, &info, 5, 1).is_err());
+		});
+}
+
+#[test]
+fn fees_keep_the_payer_alive() {
+	ExtBuilder::default()
+		.one_hundred_for_alice_n_bob_n_serper_n_settpay()
+		.build()
+		.execute_with(|| {
+			type FeeAdapter = ChargeStp258Fee<Runtime, FeeReceiver>;
+			let call = mock::Call::Stp258Currencies(crate::Call::transfer(BOB, SETT, 10));
+			let info = call.get_dispatch_info();
+
+			// ALICE holds exactly the fee
+			assert!(FeeAdapter::withdraw_fee(&ALICE, &call, &info, 100, 0).is_err());
+			assert_eq!(Stp258Currencies::free_balance(DNAR, &ALICE), 100);
+			assert_eq!(FeeAdapter::withdraw_fee(&ALICE, &call, &info, 99, 0), Ok(Some((DNAR, 99))));
+			assert_eq!(Stp258Currencies::free_balance(DNAR, &ALICE), 1);
+		});
+}
+
+#[test]
+fn fees_fall_back_to_native_currency() {
+	ExtBuilder::default()
+		.one_hundred_for_alice_n_bob_n_serper_n_settpay()
+		.build()
+		.execute_with(|| {
+			type FeeAdapter = ChargeStp258Fee<Runtime, FeeReceiver>;
+			let call = mock::Call::Stp258Currencies(crate::Call::transfer(BOB, SETT, 10));
+			let info = call.get_dispatch_info();
+			assert_ok!(Stp258Currencies::set_fee_currency(Some(ALICE).into(), Some(JUSD)));
+
+			// paused
+			assert_ok!(Stp258Currencies::pause_currency(Origin::root(), JUSD));
+			assert_eq!(FeeAdapter::withdraw_fee(&ALICE, &call, &info, 10, 0), Ok(Some((DNAR, 10))));
+			assert_ok!(Stp258Currencies::unpause_currency(Origin::root(), JUSD));
+			assert_eq!(FeeAdapter::withdraw_fee(&ALICE, &call, &info, 10, 0), Ok(Some((JUSD, 20))));
+
+			// frozen for the payer only
+			assert_ok!(Stp258Currencies::freeze_account(Origin::root(), ALICE, JUSD));
+			assert_eq!(FeeAdapter::withdraw_fee(&ALICE, &call, &info, 10, 0), Ok(Some((DNAR, 10))));
+			assert_ok!(Stp258Currencies::set_fee_currency(Some(BOB).into(), Some(JUSD)));
+			assert_eq!(FeeAdapter::withdraw_fee(&BOB, &call, &info, 10, 0), Ok(Some((JUSD, 20))));
+			assert_eq!(Stp258Currencies::free_balance(JUSD, &ALICE), 100 * 1_000 - 20);
+
+			// unpriced, which still lets the payer pick another currency
+			assert_ok!(Stp258Currencies::register_currency(Origin::root(), 4, metadata(b"XUSD", 3)));
+			assert_ok!(Stp258Currencies::set_fee_currency(Some(BOB).into(), Some(4)));
+			assert_eq!(FeeAdapter::withdraw_fee(&BOB, &call, &info, 10, 0), Ok(Some((DNAR, 10))));
+			assert_ok!(Stp258Currencies::set_fee_currency(Some(BOB).into(), None));
+		});
+}
@@ -0,0 +1,226 @@
+//! Traits extending the `serp_traits` currency abstractions.
+
+use frame_support::traits::WithdrawReasons;
+use serp_traits::{
+	BalanceStatus, LockIdentifier, Stp258AssetLockable, Stp258AssetReservable, Stp258CurrencyLockable,
+	Stp258CurrencyReservable,
+};
+use sp_runtime::{DispatchError, DispatchResult, FixedU128};
+use sp_std::result;
+
+/// The price of one currency in another.
+pub type Price = FixedU128;
+
+/// A fungible multi-currency system where funds can be reserved under a
+/// named reason, so that different reserves of the same account do not
+/// mix.
+pub trait Stp258CurrencyNamedReservable<AccountId>: Stp258CurrencyReservable<AccountId> {
+	/// An identifier for a reserve.
+	type ReserveIdentifier;
+
+	/// The amount of the balance of `who` under `currency_id` reserved
+	/// under the name `id`.
+	fn reserved_balance_named(
+		id: &Self::ReserveIdentifier,
+		currency_id: Self::CurrencyId,
+		who: &AccountId,
+	) -> Self::Balance;
+
+	/// Move `value` from the free balance of `who` under `currency_id` to
+	/// the reserve named `id`.
+	fn reserve_named(
+		id: &Self::ReserveIdentifier,
+		currency_id: Self::CurrencyId,
+		who: &AccountId,
+		value: Self::Balance,
+	) -> DispatchResult;
+
+	/// Move up to `value` of the reserve named `id` back to the free
+	/// balance. Returns the amount that could not be unreserved.
+	fn unreserve_named(
+		id: &Self::ReserveIdentifier,
+		currency_id: Self::CurrencyId,
+		who: &AccountId,
+		value: Self::Balance,
+	) -> Self::Balance;
+
+	/// Deduct up to `value` from the reserve named `id`. Returns the amount
+	/// that could not be slashed.
+	fn slash_reserved_named(
+		id: &Self::ReserveIdentifier,
+		currency_id: Self::CurrencyId,
+		who: &AccountId,
+		value: Self::Balance,
+	) -> Self::Balance;
+
+	/// Move up to `value` of the reserve named `id` of `slashed` to the
+	/// balance of `beneficiary`. If `status` is `Reserved` the funds land
+	/// in the reserve of `beneficiary` named `id`. Returns the amount that
+	/// could not be moved.
+	fn repatriate_reserved_named(
+		id: &Self::ReserveIdentifier,
+		currency_id: Self::CurrencyId,
+		slashed: &AccountId,
+		beneficiary: &AccountId,
+		value: Self::Balance,
+		status: BalanceStatus,
+	) -> result::Result<Self::Balance, DispatchError>;
+
+	/// Move the whole reserve named `id` back to the free balance. Returns
+	/// the amount that was unreserved.
+	fn unreserve_all_named(id: &Self::ReserveIdentifier, currency_id: Self::CurrencyId, who: &AccountId)
+		-> Self::Balance;
+}
+
+/// A fungible single currency system where funds can be reserved under a
+/// named reason.
+pub trait Stp258AssetNamedReservable<AccountId>: Stp258AssetReservable<AccountId> {
+	/// An identifier for a reserve.
+	type ReserveIdentifier;
+
+	/// The amount of the balance of `who` reserved under the name `id`.
+	fn reserved_balance_named(id: &Self::ReserveIdentifier, who: &AccountId) -> Self::Balance;
+
+	/// Move `value` from the free balance of `who` to the reserve named
+	/// `id`.
+	fn reserve_named(id: &Self::ReserveIdentifier, who: &AccountId, value: Self::Balance) -> DispatchResult;
+
+	/// Move up to `value` of the reserve named `id` back to the free
+	/// balance. Returns the amount that could not be unreserved.
+	fn unreserve_named(id: &Self::ReserveIdentifier, who: &AccountId, value: Self::Balance) -> Self::Balance;
+
+	/// Deduct up to `value` from the reserve named `id`. Returns the amount
+	/// that could not be slashed.
+	fn slash_reserved_named(id: &Self::ReserveIdentifier, who: &AccountId, value: Self::Balance) -> Self::Balance;
+
+	/// Move up to `value` of the reserve named `id` of `slashed` to the
+	/// balance of `beneficiary`. Returns the amount that could not be
+	/// moved.
+	fn repatriate_reserved_named(
+		id: &Self::ReserveIdentifier,
+		slashed: &AccountId,
+		beneficiary: &AccountId,
+		value: Self::Balance,
+		status: BalanceStatus,
+	) -> result::Result<Self::Balance, DispatchError>;
+
+	/// Move the whole reserve named `id` back to the free balance. Returns
+	/// the amount that was unreserved.
+	fn unreserve_all_named(id: &Self::ReserveIdentifier, who: &AccountId) -> Self::Balance;
+}
+
+/// A fungible multi-currency system whose locks only restrict the given
+/// withdraw reasons.
+pub trait Stp258CurrencyReasonLockable<AccountId>: Stp258CurrencyLockable<AccountId> {
+	/// Create or replace the lock `lock_id` on the balance of `who` under
+	/// `currency_id`, restricting only withdrawals for `reasons`.
+	fn set_lock_with_reasons(
+		lock_id: LockIdentifier,
+		currency_id: Self::CurrencyId,
+		who: &AccountId,
+		amount: Self::Balance,
+		reasons: WithdrawReasons,
+	) -> DispatchResult;
+
+	/// Extend the lock `lock_id` to at least `amount`, adding `reasons` to
+	/// the reasons it already restricts.
+	fn extend_lock_with_reasons(
+		lock_id: LockIdentifier,
+		currency_id: Self::CurrencyId,
+		who: &AccountId,
+		amount: Self::Balance,
+		reasons: WithdrawReasons,
+	) -> DispatchResult;
+
+	/// Ensure `amount` of `currency_id` can be withdrawn from `who` for
+	/// `reasons`.
+	fn ensure_can_withdraw_for(
+		currency_id: Self::CurrencyId,
+		who: &AccountId,
+		amount: Self::Balance,
+		reasons: WithdrawReasons,
+	) -> DispatchResult;
+
+	/// Withdraw `amount` of `currency_id` from `who`, failing only on the
+	/// locks restricting `reasons`, and without reaping the account.
+	fn withdraw_for(
+		currency_id: Self::CurrencyId,
+		who: &AccountId,
+		amount: Self::Balance,
+		reasons: WithdrawReasons,
+	) -> DispatchResult;
+}
+
+/// A fungible single currency system whose locks only restrict the given
+/// withdraw reasons.
+pub trait Stp258AssetReasonLockable<AccountId>: Stp258AssetLockable<AccountId> {
+	/// Create or replace the lock `lock_id` on the balance of `who`,
+	/// restricting only withdrawals for `reasons`.
+	fn set_lock_with_reasons(
+		lock_id: LockIdentifier,
+		who: &AccountId,
+		amount: Self::Balance,
+		reasons: WithdrawReasons,
+	) -> DispatchResult;
+
+	/// Extend the lock `lock_id` to at least `amount`, adding `reasons` to
+	/// the reasons it already restricts.
+	fn extend_lock_with_reasons(
+		lock_id: LockIdentifier,
+		who: &AccountId,
+		amount: Self::Balance,
+		reasons: WithdrawReasons,
+	) -> DispatchResult;
+
+	/// Ensure `amount` can be withdrawn from `who` for `reasons`.
+	fn ensure_can_withdraw_for(who: &AccountId, amount: Self::Balance, reasons: WithdrawReasons) -> DispatchResult;
+
+	/// Withdraw `amount` from `who`, failing only on the locks restricting
+	/// `reasons`, and without reaping the account.
+	fn withdraw_for(who: &AccountId, amount: Self::Balance, reasons: WithdrawReasons) -> DispatchResult;
+}
+
+/// Checked before every transfer; an error vetoes the transfer.
+pub trait PreTransfer<AccountId, CurrencyId, Balance> {
+	fn pre_transfer(currency_id: CurrencyId, from: &AccountId, to: &AccountId, amount: Balance) -> DispatchResult;
+}
+
+impl<AccountId, CurrencyId, Balance> PreTransfer<AccountId, CurrencyId, Balance> for () {
+	fn pre_transfer(_: CurrencyId, _: &AccountId, _: &AccountId, _: Balance) -> DispatchResult {
+		Ok(())
+	}
+}
+
+/// Notified after every successful transfer.
+pub trait OnTransfer<AccountId, CurrencyId, Balance> {
+	fn on_transfer(currency_id: CurrencyId, from: &AccountId, to: &AccountId, amount: Balance);
+}
+
+impl<AccountId, CurrencyId, Balance> OnTransfer<AccountId, CurrencyId, Balance> for () {
+	fn on_transfer(_: CurrencyId, _: &AccountId, _: &AccountId, _: Balance) {}
+}
+
+/// Notified after every successful deposit.
+pub trait OnDeposit<AccountId, CurrencyId, Balance> {
+	fn on_deposit(currency_id: CurrencyId, who: &AccountId, amount: Balance);
+}
+
+impl<AccountId, CurrencyId, Balance> OnDeposit<AccountId, CurrencyId, Balance> for () {
+	fn on_deposit(_: CurrencyId, _: &AccountId, _: Balance) {}
+}
+
+/// Notified after every successful withdrawal.
+pub trait OnWithdraw<AccountId, CurrencyId, Balance> {
+	fn on_withdraw(currency_id: CurrencyId, who: &AccountId, amount: Balance);
+}
+
+impl<AccountId, CurrencyId, Balance> OnWithdraw<AccountId, CurrencyId, Balance> for () {
+	fn on_withdraw(_: CurrencyId, _: &AccountId, _: Balance) {}
+}
+
+/// A source of currency prices.
+pub trait PriceProvider<CurrencyId> {
+	/// The price of one whole unit of `base` in whole units of `quote`, or
+	/// `None` if the pair has no price.
+	fn get_price(base: CurrencyId, quote: CurrencyId) -> Option<Price>;
+}
@@ -0,0 +1,60 @@
+//! Vesting schedules for the Stp258 currencies module.
+
+use codec::{Decode, Encode, HasCompact};
+use serp_traits::LockIdentifier;
+use sp_runtime::{
+	traits::{AtLeast32Bit, Saturating, UniqueSaturatedInto, Zero},
+	RuntimeDebug,
+};
+
+/// The lock under which vesting balances of every currency are held.
+pub const VESTING_LOCK_ID: LockIdentifier = *b"stp258vs";
+
+/// The vesting schedule.
+///
+/// Benefits would be granted gradually, `per_period` amount every `period`
+/// of blocks after `start`. With a `period` of one block the balance is
+/// released linearly per block.
+#[derive(Clone, Encode, Decode, PartialEq, Eq, RuntimeDebug)]
+pub struct VestingSchedule<BlockNumber, Balance: HasCompact> {
+	/// Vesting starting block
+	pub start: BlockNumber,
+	/// Number of blocks between vest
+	pub period: BlockNumber,
+	/// Number of vest
+	pub period_count: u32,
+	/// Amount of tokens to release per vest
+	#[codec(compact)]
+	pub per_period: Balance,
+}
+
+impl<BlockNumber: AtLeast32Bit + Copy, Balance: AtLeast32Bit + Copy> VestingSchedule<BlockNumber, Balance> {
+	/// Returns the end of all periods, `None` if calculation overflows.
+	pub fn end(&self) -> Option<BlockNumber> {
+		// period * period_count + start
+		self.period
+			.checked_mul(&self.period_count.into())?
+			.checked_add(&self.start)
+	}
+
+	/// Returns all locked amount, `None` if calculation overflows.
+	pub fn total_amount(&self) -> Option<Balance> {
+		self.per_period.checked_mul(&self.period_count.into())
+	}
+
+	/// Returns locked amount for a given `time`.
+	///
+	/// Note this func assumes schedule is a valid one(non-zero period and
+	/// non-overflow total amount), and it should be guaranteed by callers.
+	pub fn locked_amount(&self, time: BlockNumber) -> Balance {
+		// full = (time - start) / period
+		// unrealized = period_count - full
+		// per_period * unrealized
+		if self.period.is_zero() {
+			return Zero::zero();
+		}
+		let full = time.saturating_sub(self.start) / self.period;
+		let unrealized = self.period_count.saturating_sub(full.unique_saturated_into());
+		self.per_period.saturating_mul(unrealized.into())
+	}
+}